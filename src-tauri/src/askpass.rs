@@ -0,0 +1,205 @@
+//! jj-lib runs git as a subprocess with no stdin, so git and ssh can't prompt for credentials.
+//! Instead, they're pointed at gg itself as their askpass program; that copy of gg forwards the
+//! prompt over a loopback socket to the running app, which asks the user and sends back the answer.
+
+use std::{
+    collections::hash_map::RandomState,
+    env,
+    hash::{BuildHasher, Hasher},
+    io::{BufRead, BufReader, Write},
+    net::{TcpListener, TcpStream},
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicU64, Ordering},
+    },
+    thread,
+};
+
+use anyhow::{Result, anyhow};
+use serde::{Deserialize, Serialize};
+
+const ADDRESS_VAR: &str = "GG_ASKPASS_ADDRESS";
+const TOKEN_VAR: &str = "GG_ASKPASS_TOKEN";
+
+type Prompter = Arc<dyn Fn(&str) -> Option<String> + Send + Sync>;
+
+/// prompters of the transfers in progress; the most recent one answers
+static PROMPTERS: Mutex<Vec<(u64, Prompter)>> = Mutex::new(Vec::new());
+static NEXT_PROMPTER_ID: AtomicU64 = AtomicU64::new(1);
+
+#[derive(Serialize, Deserialize)]
+struct Request {
+    token: String,
+    prompt: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Response {
+    answer: Option<String>,
+}
+
+/// answers a prompt if this process was started by git or ssh as an askpass helper, returning
+/// the exit code
+pub fn run_helper() -> Option<i32> {
+    let address = env::var(ADDRESS_VAR).ok()?;
+    let token = env::var(TOKEN_VAR).ok()?;
+    let prompt = env::args().nth(1).unwrap_or_default();
+    match request(&address, token, prompt) {
+        Ok(Some(answer)) => {
+            println!("{answer}");
+            Some(0)
+        }
+        Ok(None) => Some(1),
+        Err(err) => {
+            eprintln!("gg askpass: {err:#}");
+            Some(1)
+        }
+    }
+}
+
+/// listens for askpass helpers and points git and ssh at them, unless the user has configured
+/// their own; must be called before any other threads are started
+pub fn start() -> Result<()> {
+    let listener = TcpListener::bind("127.0.0.1:0")?;
+    let address = listener.local_addr()?.to_string();
+    let token = new_token();
+    let exe = env::current_exe()?;
+
+    // safety: no other threads are running yet
+    unsafe {
+        env::set_var(ADDRESS_VAR, &address);
+        env::set_var(TOKEN_VAR, &token);
+        if env::var_os("GIT_ASKPASS").is_none() {
+            env::set_var("GIT_ASKPASS", &exe);
+        }
+        if env::var_os("SSH_ASKPASS").is_none() {
+            env::set_var("SSH_ASKPASS", &exe);
+            env::set_var("SSH_ASKPASS_REQUIRE", "force");
+        }
+    }
+
+    thread::spawn(move || serve(listener, token));
+    Ok(())
+}
+
+/// routes prompts to `prompter` until the returned guard is dropped
+pub fn register(prompter: impl Fn(&str) -> Option<String> + Send + Sync + 'static) -> Registration {
+    let id = NEXT_PROMPTER_ID.fetch_add(1, Ordering::SeqCst);
+    PROMPTERS
+        .lock()
+        .expect("prompter mutex poisoned")
+        .push((id, Arc::new(prompter)));
+    Registration(id)
+}
+
+pub struct Registration(u64);
+
+impl Drop for Registration {
+    fn drop(&mut self) {
+        PROMPTERS
+            .lock()
+            .expect("prompter mutex poisoned")
+            .retain(|(id, _)| *id != self.0);
+    }
+}
+
+fn serve(listener: TcpListener, token: String) {
+    for stream in listener.incoming() {
+        match stream {
+            Ok(stream) => {
+                let token = token.clone();
+                thread::spawn(move || {
+                    if let Err(err) = answer(stream, &token) {
+                        log::warn!("askpass request failed: {err:#}");
+                    }
+                });
+            }
+            Err(err) => log::warn!("askpass connection failed: {err}"),
+        }
+    }
+}
+
+fn answer(stream: TcpStream, token: &str) -> Result<()> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+    let request: Request = serde_json::from_str(&line)?;
+    if request.token != token {
+        return Err(anyhow!("invalid token"));
+    }
+
+    // the lock isn't held while the user answers
+    let prompter = PROMPTERS
+        .lock()
+        .expect("prompter mutex poisoned")
+        .last()
+        .map(|(_, prompter)| prompter.clone());
+    let answer = prompter.and_then(|prompter| prompter(&request.prompt));
+
+    let mut stream = stream;
+    serde_json::to_writer(&mut stream, &Response { answer })?;
+    stream.write_all(b"\n")?;
+    Ok(())
+}
+
+fn request(address: &str, token: String, prompt: String) -> Result<Option<String>> {
+    let mut stream = TcpStream::connect(address)?;
+    serde_json::to_writer(&mut stream, &Request { token, prompt })?;
+    stream.write_all(b"\n")?;
+
+    let mut line = String::new();
+    BufReader::new(stream).read_line(&mut line)?;
+    let response: Response = serde_json::from_str(&line)?;
+    Ok(response.answer)
+}
+
+fn new_token() -> String {
+    // each RandomState is seeded randomly by the OS
+    let mut token = String::new();
+    for _ in 0..2 {
+        let hasher = RandomState::new().build_hasher();
+        token.push_str(&format!("{:016x}", hasher.finish()));
+    }
+    token
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prompt_round_trip() -> Result<()> {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let address = listener.local_addr()?.to_string();
+        thread::spawn(move || serve(listener, "secret".to_owned()));
+
+        let _registration =
+            register(|prompt| prompt.starts_with("Password").then(|| "hunter2".to_owned()));
+
+        let answer = request(
+            &address,
+            "secret".to_owned(),
+            "Password for 'x': ".to_owned(),
+        )?;
+        assert_eq!(answer.as_deref(), Some("hunter2"));
+
+        let answer = request(
+            &address,
+            "secret".to_owned(),
+            "Username for 'x': ".to_owned(),
+        )?;
+        assert_eq!(answer, None);
+
+        // a connection without the token gets nothing
+        assert!(
+            request(
+                &address,
+                "guess".to_owned(),
+                "Password for 'x': ".to_owned()
+            )
+            .is_err()
+        );
+
+        Ok(())
+    }
+}
@@ -1,19 +1,18 @@
 //! Sometimes callbacks are buried deep in library code, requiring user input.
 //! This module offers an overcomplicated and fragile solution.
 
-use std::{
-    collections::HashMap,
-    path::{Path, PathBuf},
-    sync::mpsc::channel,
-};
+use std::{cell::Cell, collections::HashMap, sync::mpsc::channel};
 
 use anyhow::Result;
-use jj_lib::{git::RemoteCallbacks, repo::MutableRepo};
+use jj_lib::{
+    git::{Progress, RemoteCallbacks},
+    repo::MutableRepo,
+};
 use tauri::{Emitter, Manager, Window};
 
 use crate::{
-    AppState,
-    messages::{InputField, InputRequest},
+    AppState, askpass,
+    messages::{InputField, InputRequest, ProgressEvent},
     worker::WorkerCallbacks,
 };
 
@@ -23,43 +22,78 @@ impl WorkerCallbacks for FrontendCallbacks {
     fn with_git(
         &self,
         repo: &mut MutableRepo,
+        operation: &str,
+        cancel_id: Option<u32>,
         f: &dyn Fn(&mut MutableRepo, RemoteCallbacks<'_>) -> Result<()>,
     ) -> Result<()> {
         let mut cb = RemoteCallbacks::default();
 
-        let get_ssh_keys = &mut get_ssh_keys;
-        cb.get_ssh_keys = Some(get_ssh_keys);
-
-        let get_password = &mut |url: &str, username: &str| {
-            self.request_input(
-                format!("Please enter a password for {} at {}", username, url),
-                ["Password"],
-            )
-            .and_then(|mut fields| fields.remove("Password"))
-        };
-        cb.get_password = Some(get_password);
-
-        let get_username_password = &mut |url: &str| {
-            self.request_input(
-                format!("Please enter a username and password for {}", url),
-                ["Username", "Password"],
-            )
-            .and_then(|mut fields| {
-                fields.remove("Username").and_then(|username| {
-                    fields
-                        .remove("Password")
-                        .map(|password| (username, password))
-                })
-            })
+        // git reports progress very frequently, so only whole percentages are forwarded
+        let last_percent = Cell::new(None);
+        let progress = &mut |progress: &Progress| {
+            let percent = (progress.overall * 100.0) as u32;
+            if last_percent.replace(Some(percent)) != Some(percent) {
+                self.report_progress(ProgressEvent {
+                    operation: operation.to_owned(),
+                    fraction: Some(progress.overall),
+                    detail: progress.bytes_downloaded.map(format_bytes),
+                    cancel_id,
+                    finished: false,
+                });
+            }
         };
-        cb.get_username_password = Some(get_username_password);
+        cb.progress = Some(progress);
 
-        f(repo, cb)
+        // git and ssh ask for credentials through gg's askpass helper, which forwards them here
+        let prompter = FrontendCallbacks(self.0.clone());
+        let _askpass = askpass::register(move |prompt| prompter.answer_prompt(prompt));
+
+        self.report_progress(ProgressEvent {
+            operation: operation.to_owned(),
+            fraction: None,
+            detail: None,
+            cancel_id,
+            finished: false,
+        });
+        let result = f(repo, cb);
+        self.report_progress(ProgressEvent {
+            operation: operation.to_owned(),
+            fraction: None,
+            detail: None,
+            cancel_id,
+            finished: true,
+        });
+
+        result
+    }
+
+    fn report_progress(&self, progress: ProgressEvent) {
+        if let Err(err) = self.0.emit("gg://progress", progress) {
+            log::warn!("progress report failed: {err}");
+        }
+    }
+
+    fn progress_reporter(&self) -> Box<dyn Fn(ProgressEvent) + Send + Sync> {
+        let reporter = FrontendCallbacks(self.0.clone());
+        Box::new(move |progress| reporter.report_progress(progress))
     }
 }
 
 impl FrontendCallbacks {
-    #[expect(unused)]
+    /// git asks for usernames and passwords separately; ssh asks for passphrases and host key
+    /// confirmations
+    fn answer_prompt(&self, prompt: &str) -> Option<String> {
+        let label = if prompt.starts_with("Username") {
+            "Username"
+        } else if prompt.to_lowercase().contains("pass") {
+            "Password"
+        } else {
+            "Response"
+        };
+        self.request_input(prompt.trim().to_owned(), [label])
+            .and_then(|mut fields| fields.remove(label))
+    }
+
     fn request_input<T: IntoIterator<Item = U>, U: Into<InputField>>(
         &self,
         detail: String,
@@ -105,22 +139,17 @@ impl FrontendCallbacks {
     }
 }
 
-#[expect(unused)]
-// simplistic, but it's the same as the version in jj_cli::git_util
-fn get_ssh_keys(_username: &str) -> Vec<PathBuf> {
-    let mut paths = vec![];
-    if let Some(home_dir) = dirs::home_dir() {
-        let ssh_dir = Path::new(&home_dir).join(".ssh");
-        for filename in ["id_ed25519_sk", "id_ed25519", "id_rsa"] {
-            let key_path = ssh_dir.join(filename);
-            if key_path.is_file() {
-                log::info!("found ssh key {key_path:?}");
-                paths.push(key_path);
-            }
-        }
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
     }
-    if paths.is_empty() {
-        log::info!("no ssh key found");
+    if unit == 0 {
+        format!("{bytes} B")
+    } else {
+        format!("{value:.1} {}", UNITS[unit])
     }
-    paths
 }
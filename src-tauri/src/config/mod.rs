@@ -18,6 +18,7 @@ pub trait GGSettings {
     fn ui_theme_override(&self) -> Option<String>;
     fn ui_mark_unpushed_bookmarks(&self) -> bool;
     fn ui_track_recent_workspaces(&self) -> bool;
+    fn git_push_bookmark_prefix(&self) -> String;
     #[allow(dead_code)]
     fn ui_recent_workspaces(&self) -> Vec<String>;
 }
@@ -52,6 +53,11 @@ impl GGSettings for UserSettings {
             .unwrap_or(true)
     }
 
+    fn git_push_bookmark_prefix(&self) -> String {
+        self.get_string("gg.git.push-bookmark-prefix")
+            .unwrap_or(String::from("push-"))
+    }
+
     fn ui_recent_workspaces(&self) -> Vec<String> {
         self.get_value("gg.ui.recent-workspaces")
             .ok()
@@ -217,3 +217,20 @@ fn recent_workspaces_returns_configured_paths() {
         vec!["/path/one".to_string(), "/path/two".to_string()]
     );
 }
+
+#[test]
+fn push_bookmark_prefix_default() {
+    let settings = settings_with_gg_defaults();
+    assert_eq!(settings.git_push_bookmark_prefix(), "push-");
+}
+
+#[test]
+fn push_bookmark_prefix_can_be_overridden() {
+    let settings = settings_with_overrides(
+        r#"
+            [gg.git]
+            push-bookmark-prefix = "review/"
+            "#,
+    );
+    assert_eq!(settings.git_push_bookmark_prefix(), "review/");
+}
@@ -1,5 +1,6 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+mod askpass;
 mod callbacks;
 mod config;
 mod handler;
@@ -25,12 +26,17 @@ use tauri_plugin_window_state::StateFlags;
 use windows::Win32::System::Console::{ATTACH_PARENT_PROCESS, AttachConsole};
 
 use messages::{
-    AbandonRevisions, BackoutRevisions, CheckoutRevision, CopyChanges, CreateRef, CreateRevision,
-    CreateRevisionBetween, DeleteRef, DescribeRevision, DuplicateRevisions, GitFetch, GitPush,
-    InputResponse, InsertRevision, MoveChanges, MoveHunk, MoveRef, MoveRevision, MoveSource,
-    MutationResult, RenameBranch, RevId, TrackBranch, UndoOperation, UntrackBranch,
+    AbandonRevisions, AbsorbChanges, AddRemote, BackoutRevisions, CheckoutRevision, CopyChanges,
+    CreateRef, CreateRevision, CreateRevisionBetween, DeleteRef, DescribeRevision,
+    DuplicateRevisions, GitFetch, GitPush, GitPushStack, InputResponse, InsertRevision,
+    MoveChanges, MoveHunk, MoveRef, MoveRevision, MoveSource, MutationResult, RedoOperation,
+    RemoveRemote, RenameBranch, RenameRemote, ReorderRevisions, RestoreHiddenRevision,
+    RestoreOperation, RevId, RevertOperation, SetRemoteUrl, SplitRevision, SquashRevision,
+    TrackBranch, UndoOperation, UntrackBranch,
+};
+use worker::{
+    CancellationToken, Mutation, Session, SessionEvent, ViewingPastOperation, WorkerSession,
 };
-use worker::{Mutation, Session, SessionEvent, WorkerSession};
 
 use crate::callbacks::FrontendCallbacks;
 use crate::messages::CopyHunk;
@@ -53,6 +59,10 @@ struct AppState(Mutex<HashMap<String, WindowState>>);
 struct WindowState {
     _worker: JoinHandle<()>,
     worker_channel: Sender<SessionEvent>,
+    cancellation: CancellationToken,
+    background_cancellations: Vec<CancellationToken>,
+    workspace_path: Option<PathBuf>,
+    viewing_past_operation: bool,
     input_channel: Option<Sender<InputResponse>>,
 }
 
@@ -67,6 +77,68 @@ impl AppState {
             .clone()
     }
 
+    fn cancel(&self, window_label: &str, id: u32) {
+        let state = self.0.lock().expect("state mutex poisoned");
+        let window_state = state.get(window_label).expect("session not found");
+        window_state.cancellation.cancel(id);
+        for cancellation in &window_state.background_cancellations {
+            cancellation.cancel(id);
+        }
+    }
+
+    /// registers a cancellation token for a background operation on the open workspace, if any
+    fn start_background_session(&self, window_label: &str) -> Option<(CancellationToken, PathBuf)> {
+        let mut state = self.0.lock().expect("state mutex poisoned");
+        let window_state = state.get_mut(window_label).expect("session not found");
+        let workspace_path = window_state.workspace_path.clone()?;
+        let cancellation = CancellationToken::default();
+        window_state
+            .background_cancellations
+            .push(cancellation.clone());
+        Some((cancellation, workspace_path))
+    }
+
+    fn end_background_session(&self, window_label: &str, cancellation: &CancellationToken) {
+        self.0
+            .lock()
+            .expect("state mutex poisoned")
+            .get_mut(window_label)
+            .expect("session not found")
+            .background_cancellations
+            .retain(|other| other != cancellation);
+    }
+
+    fn set_workspace_path(&self, window_label: &str, path: Option<PathBuf>) {
+        self.0
+            .lock()
+            .expect("state mutex poisoned")
+            .get_mut(window_label)
+            .expect("session not found")
+            .workspace_path = path;
+    }
+
+    /// tracks the worker's read-only mode, which background operations can't ask it about
+    fn set_viewing_past_operation(&self, window_label: &str, config: &messages::RepoConfig) {
+        self.0
+            .lock()
+            .expect("state mutex poisoned")
+            .get_mut(window_label)
+            .expect("session not found")
+            .viewing_past_operation = matches!(
+            config,
+            messages::RepoConfig::Workspace { status, .. } if status.historical_operation.is_some()
+        );
+    }
+
+    fn is_viewing_past_operation(&self, window_label: &str) -> bool {
+        self.0
+            .lock()
+            .expect("state mutex poisoned")
+            .get(window_label)
+            .expect("session not found")
+            .viewing_past_operation
+    }
+
     #[allow(unused)]
     fn set_input(&self, window_label: &str, tx: Sender<InputResponse>) {
         self.0
@@ -89,6 +161,11 @@ impl AppState {
 }
 
 fn main() -> Result<()> {
+    // git may run gg as its askpass program, in which case there's no app to start
+    if let Some(exit_code) = askpass::run_helper() {
+        std::process::exit(exit_code);
+    }
+
     // before parsing args, attach a console on windows
     // will fail if not started from a shell, but that's fine
     // safety: FFI
@@ -97,6 +174,11 @@ fn main() -> Result<()> {
 
     let args = Args::parse();
 
+    // credential prompts will fail, but transfers which don't need them still work
+    if let Err(err) = askpass::start() {
+        eprintln!("askpass unavailable: {err:#}");
+    }
+
     tauri::Builder::default()
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_dialog::init())
@@ -134,11 +216,17 @@ fn main() -> Result<()> {
         .invoke_handler(tauri::generate_handler![
             notify_window_ready,
             notify_input,
+            cancel_operation,
             forward_accelerator,
             query_log,
             query_log_next_page,
             query_revision,
+            query_evolution,
             query_remotes,
+            query_operations,
+            query_operation_diff,
+            query_push_plan,
+            view_operation,
             abandon_revisions,
             backout_revisions,
             checkout_revision,
@@ -149,10 +237,15 @@ fn main() -> Result<()> {
             insert_revision,
             move_revision,
             move_source,
+            reorder_revisions,
             move_changes,
+            squash_revision,
+            absorb_changes,
             copy_changes,
             move_hunk,
             copy_hunk,
+            split_revision,
+            restore_hidden_revision,
             track_branch,
             untrack_branch,
             rename_branch,
@@ -160,10 +253,19 @@ fn main() -> Result<()> {
             delete_ref,
             move_ref,
             git_push,
+            git_push_stack,
             git_fetch,
+            add_remote,
+            remove_remote,
+            rename_remote,
+            set_remote_url,
             undo_operation,
+            redo_operation,
+            restore_operation,
+            revert_operation,
             query_recent_workspaces,
             open_workspace_at_path,
+            create_workspace,
             menu_repo_open,
             menu_repo_reopen,
         ])
@@ -172,10 +274,17 @@ fn main() -> Result<()> {
                 .get_webview_window("main")
                 .ok_or(anyhow!("preconfigured window not found"))?;
             let (sender, receiver) = channel();
+            let cancellation = CancellationToken::default();
 
             let mut handle = window.as_ref().window();
+            let worker_cancellation = cancellation.clone();
             let window_worker = thread::spawn(move || {
-                async_runtime::block_on(work(handle.clone(), receiver, args.workspace))
+                async_runtime::block_on(work(
+                    handle.clone(),
+                    receiver,
+                    worker_cancellation,
+                    args.workspace,
+                ))
             });
 
             handle = window.as_ref().window();
@@ -187,6 +296,10 @@ fn main() -> Result<()> {
                 WindowState {
                     _worker: window_worker,
                     worker_channel: sender,
+                    cancellation,
+                    background_cancellations: Vec::new(),
+                    workspace_path: None,
+                    viewing_past_operation: false,
                     input_channel: None,
                 },
             );
@@ -202,14 +315,19 @@ fn main() -> Result<()> {
 async fn work(
     window: Window,
     rx: std::sync::mpsc::Receiver<SessionEvent>,
+    cancellation: CancellationToken,
     workspace: Option<PathBuf>,
 ) {
     log::info!("start worker");
 
-    while let Err(err) = WorkerSession::new(FrontendCallbacks(window.clone()), workspace.clone())
-        .handle_events(&rx)
-        .await
-        .context("worker")
+    while let Err(err) = WorkerSession::new(
+        FrontendCallbacks(window.clone()),
+        cancellation.clone(),
+        workspace.clone(),
+    )
+    .handle_events(&rx)
+    .await
+    .context("worker")
     {
         log::info!("restart worker: {err:#}");
 
@@ -243,6 +361,12 @@ fn notify_input(
     response_tx.send(response).map_err(InvokeError::from_error)
 }
 
+#[tauri::command]
+fn cancel_operation(window: Window, app_state: State<AppState>, id: u32) {
+    log::debug!("cancelling worker operation {id}");
+    app_state.cancel(window.label(), id);
+}
+
 #[tauri::command]
 fn forward_accelerator(window: Window, key: char) {
     if key == 'o' {
@@ -306,6 +430,24 @@ fn query_revision(
         .map_err(InvokeError::from_anyhow)
 }
 
+#[tauri::command(async)]
+fn query_evolution(
+    window: Window,
+    app_state: State<AppState>,
+    id: RevId,
+) -> Result<messages::EvolutionResult, InvokeError> {
+    let session_tx: Sender<SessionEvent> = app_state.get_session(window.label());
+    let (call_tx, call_rx) = channel();
+
+    session_tx
+        .send(SessionEvent::QueryEvolution { tx: call_tx, id })
+        .map_err(InvokeError::from_error)?;
+    call_rx
+        .recv()
+        .map_err(InvokeError::from_error)?
+        .map_err(InvokeError::from_anyhow)
+}
+
 #[tauri::command(async)]
 fn query_remotes(
     window: Window,
@@ -327,6 +469,92 @@ fn query_remotes(
         .map_err(InvokeError::from_anyhow)
 }
 
+#[tauri::command(async)]
+fn query_operations(
+    window: Window,
+    app_state: State<AppState>,
+    skip: usize,
+    max_results: usize,
+) -> Result<messages::OperationPage, InvokeError> {
+    let session_tx: Sender<SessionEvent> = app_state.get_session(window.label());
+    let (call_tx, call_rx) = channel();
+
+    session_tx
+        .send(SessionEvent::QueryOperations {
+            tx: call_tx,
+            skip,
+            max_results,
+        })
+        .map_err(InvokeError::from_error)?;
+    call_rx
+        .recv()
+        .map_err(InvokeError::from_error)?
+        .map_err(InvokeError::from_anyhow)
+}
+
+#[tauri::command(async)]
+fn query_operation_diff(
+    window: Window,
+    app_state: State<AppState>,
+    from_id: Option<messages::OperationId>,
+    to_id: messages::OperationId,
+) -> Result<messages::OperationDiff, InvokeError> {
+    let session_tx: Sender<SessionEvent> = app_state.get_session(window.label());
+    let (call_tx, call_rx) = channel();
+
+    session_tx
+        .send(SessionEvent::QueryOperationDiff {
+            tx: call_tx,
+            from_id,
+            to_id,
+        })
+        .map_err(InvokeError::from_error)?;
+    call_rx
+        .recv()
+        .map_err(InvokeError::from_error)?
+        .map_err(InvokeError::from_anyhow)
+}
+
+#[tauri::command(async)]
+fn query_push_plan(
+    window: Window,
+    app_state: State<AppState>,
+    push: GitPush,
+) -> Result<messages::PushPlan, InvokeError> {
+    let session_tx: Sender<SessionEvent> = app_state.get_session(window.label());
+    let (call_tx, call_rx) = channel();
+
+    session_tx
+        .send(SessionEvent::QueryPushPlan { tx: call_tx, push })
+        .map_err(InvokeError::from_error)?;
+    call_rx
+        .recv()
+        .map_err(InvokeError::from_error)?
+        .map_err(InvokeError::from_anyhow)
+}
+
+#[tauri::command(async)]
+fn view_operation(
+    window: Window,
+    app_state: State<AppState>,
+    op_id: Option<messages::OperationId>,
+) -> Result<(), InvokeError> {
+    let session_tx: Sender<SessionEvent> = app_state.get_session(window.label());
+    let (call_tx, call_rx) = channel();
+
+    session_tx
+        .send(SessionEvent::ViewOperation { tx: call_tx, op_id })
+        .map_err(InvokeError::from_error)?;
+    let config = call_rx
+        .recv()
+        .map_err(InvokeError::from_error)?
+        .map_err(InvokeError::from_anyhow)?;
+    app_state.set_viewing_past_operation(window.label(), &config);
+    window
+        .emit("gg://repo/config", config)
+        .map_err(InvokeError::from_error)
+}
+
 #[tauri::command(async)]
 fn abandon_revisions(
     window: Window,
@@ -417,6 +645,15 @@ fn move_source(
     try_mutate(window, app_state, mutation)
 }
 
+#[tauri::command(async)]
+fn reorder_revisions(
+    window: Window,
+    app_state: State<AppState>,
+    mutation: ReorderRevisions,
+) -> Result<MutationResult, InvokeError> {
+    try_mutate(window, app_state, mutation)
+}
+
 #[tauri::command(async)]
 fn move_changes(
     window: Window,
@@ -426,6 +663,24 @@ fn move_changes(
     try_mutate(window, app_state, mutation)
 }
 
+#[tauri::command(async)]
+fn squash_revision(
+    window: Window,
+    app_state: State<AppState>,
+    mutation: SquashRevision,
+) -> Result<MutationResult, InvokeError> {
+    try_mutate(window, app_state, mutation)
+}
+
+#[tauri::command(async)]
+fn absorb_changes(
+    window: Window,
+    app_state: State<AppState>,
+    mutation: AbsorbChanges,
+) -> Result<MutationResult, InvokeError> {
+    try_mutate(window, app_state, mutation)
+}
+
 #[tauri::command(async)]
 fn copy_changes(
     window: Window,
@@ -453,6 +708,24 @@ fn copy_hunk(
     try_mutate(window, app_state, mutation)
 }
 
+#[tauri::command(async)]
+fn split_revision(
+    window: Window,
+    app_state: State<AppState>,
+    mutation: SplitRevision,
+) -> Result<MutationResult, InvokeError> {
+    try_mutate(window, app_state, mutation)
+}
+
+#[tauri::command(async)]
+fn restore_hidden_revision(
+    window: Window,
+    app_state: State<AppState>,
+    mutation: RestoreHiddenRevision,
+) -> Result<MutationResult, InvokeError> {
+    try_mutate(window, app_state, mutation)
+}
+
 #[tauri::command(async)]
 fn track_branch(
     window: Window,
@@ -513,7 +786,16 @@ fn git_push(
     app_state: State<AppState>,
     mutation: GitPush,
 ) -> Result<MutationResult, InvokeError> {
-    try_mutate(window, app_state, mutation)
+    try_mutate_in_background(window, app_state, mutation)
+}
+
+#[tauri::command(async)]
+fn git_push_stack(
+    window: Window,
+    app_state: State<AppState>,
+    mutation: GitPushStack,
+) -> Result<MutationResult, InvokeError> {
+    try_mutate_in_background(window, app_state, mutation)
 }
 
 #[tauri::command(async)]
@@ -522,7 +804,43 @@ fn git_fetch(
     app_state: State<AppState>,
     mutation: GitFetch,
 ) -> Result<MutationResult, InvokeError> {
-    try_mutate(window, app_state, mutation)
+    try_mutate_in_background(window, app_state, mutation)
+}
+
+#[tauri::command(async)]
+fn add_remote(
+    window: Window,
+    app_state: State<AppState>,
+    mutation: AddRemote,
+) -> Result<MutationResult, InvokeError> {
+    try_mutate_remotes(window, app_state, mutation)
+}
+
+#[tauri::command(async)]
+fn remove_remote(
+    window: Window,
+    app_state: State<AppState>,
+    mutation: RemoveRemote,
+) -> Result<MutationResult, InvokeError> {
+    try_mutate_remotes(window, app_state, mutation)
+}
+
+#[tauri::command(async)]
+fn rename_remote(
+    window: Window,
+    app_state: State<AppState>,
+    mutation: RenameRemote,
+) -> Result<MutationResult, InvokeError> {
+    try_mutate_remotes(window, app_state, mutation)
+}
+
+#[tauri::command(async)]
+fn set_remote_url(
+    window: Window,
+    app_state: State<AppState>,
+    mutation: SetRemoteUrl,
+) -> Result<MutationResult, InvokeError> {
+    try_mutate_remotes(window, app_state, mutation)
 }
 
 #[tauri::command(async)]
@@ -533,6 +851,32 @@ fn undo_operation(
     try_mutate(window, app_state, UndoOperation)
 }
 
+#[tauri::command(async)]
+fn redo_operation(
+    window: Window,
+    app_state: State<AppState>,
+) -> Result<MutationResult, InvokeError> {
+    try_mutate(window, app_state, RedoOperation)
+}
+
+#[tauri::command(async)]
+fn restore_operation(
+    window: Window,
+    app_state: State<AppState>,
+    mutation: RestoreOperation,
+) -> Result<MutationResult, InvokeError> {
+    try_mutate(window, app_state, mutation)
+}
+
+#[tauri::command(async)]
+fn revert_operation(
+    window: Window,
+    app_state: State<AppState>,
+    mutation: RevertOperation,
+) -> Result<MutationResult, InvokeError> {
+    try_mutate(window, app_state, mutation)
+}
+
 fn try_open_repository(window: &Window, cwd: Option<PathBuf>) -> Result<()> {
     log::info!("load workspace {cwd:#?}");
 
@@ -556,6 +900,8 @@ fn try_open_repository(window: &Window, cwd: Option<PathBuf>) -> Result<()> {
             } = &config
             {
                 let repo_path = absolute_path.0.clone();
+                app_state.set_workspace_path(window.label(), Some(PathBuf::from(&repo_path)));
+
                 // update config and jump lists - this can be slow
                 if *track_recent_workspaces {
                     let window = window.clone();
@@ -564,10 +910,12 @@ fn try_open_repository(window: &Window, cwd: Option<PathBuf>) -> Result<()> {
                     });
                 }
             }
+            app_state.set_viewing_past_operation(window.label(), &config);
             window.emit("gg://repo/config", config)?;
         }
         Err(err) => {
             log::warn!("load workspace failed: {err}");
+            app_state.set_workspace_path(window.label(), None);
             window.emit(
                 "gg://repo/config",
                 messages::RepoConfig::LoadError {
@@ -598,6 +946,94 @@ fn try_mutate<T: Mutation + Send + Sync + 'static>(
     call_rx.recv().map_err(InvokeError::from_error)
 }
 
+/// runs a mutation which edits the git remotes, then reopens the workspace - jj-lib caches the
+/// git config, so the worker and frontend wouldn't see the new remotes otherwise
+fn try_mutate_remotes<T: Mutation + Send + Sync + 'static>(
+    window: Window,
+    app_state: State<AppState>,
+    mutation: T,
+) -> Result<MutationResult, InvokeError> {
+    let result = try_mutate(window.clone(), app_state, mutation)?;
+    if let MutationResult::Updated { .. } | MutationResult::Unchanged = result {
+        try_open_repository(&window, None).map_err(InvokeError::from_anyhow)?;
+    }
+    Ok(result)
+}
+
+/// runs a slow mutation, such as a git transfer, on its own thread with a separate repo handle;
+/// the window's worker keeps answering queries meanwhile, then reloads when it's done
+fn try_mutate_in_background<T: Mutation + Send + Sync + 'static>(
+    window: Window,
+    app_state: State<AppState>,
+    mutation: T,
+) -> Result<MutationResult, InvokeError> {
+    // the window's worker would reject this, so the background session must too
+    if app_state.is_viewing_past_operation(window.label()) {
+        return Ok(MutationResult::PreconditionError {
+            message: ViewingPastOperation.to_string(),
+        });
+    }
+
+    let (cancellation, workspace_path) = app_state
+        .start_background_session(window.label())
+        .ok_or(anyhow!("No workspace is open"))
+        .map_err(InvokeError::from_anyhow)?;
+
+    let (session_tx, session_rx) = channel();
+    let (config_tx, config_rx) = channel();
+    let (call_tx, call_rx) = channel();
+    session_tx
+        .send(SessionEvent::OpenWorkspace {
+            tx: config_tx,
+            wd: Some(workspace_path.clone()),
+        })
+        .map_err(InvokeError::from_error)?;
+    session_tx
+        .send(SessionEvent::ExecuteMutation {
+            tx: call_tx,
+            mutation: Box::new(mutation),
+        })
+        .map_err(InvokeError::from_error)?;
+    session_tx
+        .send(SessionEvent::EndSession)
+        .map_err(InvokeError::from_error)?;
+
+    let worker_window = window.clone();
+    thread::spawn(move || {
+        let callbacks = FrontendCallbacks(worker_window.clone());
+        let session = WorkerSession::new(callbacks, cancellation.clone(), Some(workspace_path));
+        if let Err(err) = async_runtime::block_on(session.handle_events(&session_rx)) {
+            log::error!("background worker: {err:#}");
+        }
+        worker_window
+            .state::<AppState>()
+            .end_background_session(worker_window.label(), &cancellation);
+    });
+
+    match config_rx.recv().map_err(InvokeError::from_error)? {
+        Ok(messages::RepoConfig::Workspace { .. }) => (),
+        Ok(_) => {
+            return Err(InvokeError::from_anyhow(anyhow!(
+                "Failed to load workspace for background operation"
+            )));
+        }
+        Err(err) => return Err(InvokeError::from_anyhow(err)),
+    }
+    let result = call_rx.recv().map_err(InvokeError::from_error)?;
+
+    // the window's worker may be busy, so don't wait for it to catch up
+    let session_tx = app_state.get_session(window.label());
+    thread::spawn(move || {
+        let (call_tx, call_rx) = channel();
+        handler::nonfatal!(session_tx.send(SessionEvent::ExecuteSnapshot { tx: call_tx }));
+        if let Some(status) = handler::nonfatal!(call_rx.recv()) {
+            handler::nonfatal!(window.emit("gg://repo/status", status));
+        }
+    });
+
+    Ok(result)
+}
+
 fn handle_window_event(window: &Window, event: &WindowEvent) {
     if let WindowEvent::Focused(true) = *event {
         log::debug!("window focused; requesting snapshot");
@@ -689,6 +1125,39 @@ fn open_workspace_at_path(window: Window, path: String) -> Result<(), InvokeErro
     }
 }
 
+/// initializes or clones a repo, asking where to put it unless a path is given, then opens it
+#[tauri::command(async)]
+fn create_workspace(
+    window: Window,
+    app_state: State<AppState>,
+    path: Option<String>,
+    source: messages::WorkspaceSource,
+) -> Result<(), InvokeError> {
+    let wd = match path {
+        Some(path) => PathBuf::from(path),
+        None => match window.dialog().file().blocking_pick_folder() {
+            Some(FilePath::Path(wd)) => wd,
+            _ => return Ok(()),
+        },
+    };
+
+    let session_tx: Sender<SessionEvent> = app_state.get_session(window.label());
+    let (call_tx, call_rx) = channel();
+    session_tx
+        .send(SessionEvent::CreateWorkspace {
+            tx: call_tx,
+            wd: wd.clone(),
+            source,
+        })
+        .map_err(InvokeError::from_error)?;
+    call_rx
+        .recv()
+        .map_err(InvokeError::from_error)?
+        .map_err(InvokeError::from_anyhow)?;
+
+    try_open_repository(&window, Some(wd)).map_err(InvokeError::from_anyhow)
+}
+
 #[tauri::command]
 fn menu_repo_open(window: Window) {
     open_repo(&window);
@@ -80,11 +80,43 @@ pub enum RepoConfig {
     },
 }
 
+/// How to create a workspace in a directory which doesn't have one
+#[derive(Deserialize, Debug, TS)]
+#[serde(tag = "type")]
+#[ts(export, export_to = "../../src/messages/")]
+pub enum WorkspaceSource {
+    /// A new, empty repo
+    Init { colocate: bool },
+    /// Shares the git repo already in the directory
+    Colocate,
+    /// Copies a repo from a URL or local path, which becomes the origin remote
+    Clone { source: String, colocate: bool },
+}
+
 #[derive(Serialize, Clone, Debug, TS)]
 #[ts(export, export_to = "../../src/messages/")]
 pub struct RepoStatus {
     pub operation_description: String,
     pub working_copy: CommitId,
+    /// Set when viewing the repo at a past operation, in which case it can't be modified
+    pub historical_operation: Option<OperationId>,
+    /// Includes gg.mutation, gg.changes and gg.version for operations created by gg
+    pub operation_tags: HashMap<String, String>,
+    /// New files which the latest snapshot left untracked because they were too large
+    pub oversized_files: Vec<OversizedFile>,
+    /// Set when the latest snapshot failed, e.g. because the working copy is stale
+    pub snapshot_error: Option<String>,
+}
+
+/// A new file too large to snapshot, according to snapshot.max-new-file-size
+#[derive(Serialize, Clone, Debug, TS)]
+#[ts(export, export_to = "../../src/messages/")]
+pub struct OversizedFile {
+    pub path: TreePath,
+    #[ts(type = "number")]
+    pub size: u64,
+    #[ts(type = "number")]
+    pub max_size: u64,
 }
 
 /// Bookmark or tag name with metadata.
@@ -187,3 +219,17 @@ impl From<&str> for InputField {
         }
     }
 }
+
+/// Status of a long-running worker operation, such as a git transfer or snapshot
+#[derive(Serialize, Debug, Clone, TS)]
+#[ts(export, export_to = "../../src/messages/")]
+pub struct ProgressEvent {
+    pub operation: String,
+    /// Between 0 and 1, or absent if the total amount of work is unknown
+    pub fraction: Option<f32>,
+    pub detail: Option<String>,
+    /// Passed to cancel_operation to skip any remaining steps; absent if there are none, since a
+    /// transfer in flight always completes
+    pub cancel_id: Option<u32>,
+    pub finished: bool,
+}
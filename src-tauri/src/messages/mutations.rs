@@ -20,6 +20,71 @@ pub enum MutationResult {
     InternalError {
         message: MultilineString,
     },
+    /// Stopped by a cancel_operation request; nothing was changed
+    Cancelled,
+    /// A stack push ran, though the remote may have rejected some of its bookmarks
+    UpdatedPushes {
+        new_status: RepoStatus,
+        pushes: Vec<RevisionPush>,
+    },
+    /// Hunks were absorbed into the listed revisions; any skipped paths stay in the source
+    UpdatedAbsorb {
+        new_status: RepoStatus,
+        absorbed: Vec<AbsorbedChanges>,
+        skipped_paths: Vec<SkippedPath>,
+    },
+    /// Revisions were rewritten in the listed order, each with any paths which now conflict
+    UpdatedReorder {
+        new_status: RepoStatus,
+        revisions: Vec<ReorderedRevision>,
+    },
+    /// Part of the work was committed, but some steps failed; the message lists them
+    UpdatedWithErrors {
+        new_status: RepoStatus,
+        message: String,
+    },
+}
+
+/// The hunks one revision received from an absorb
+#[derive(Serialize, Clone, Debug, TS)]
+#[ts(export, export_to = "../../src/messages/")]
+pub struct AbsorbedChanges {
+    pub revision: RevHeader,
+    pub changes: Vec<RevChange>,
+}
+
+/// A path whose changes couldn't be absorbed, such as a conflicted or binary file
+#[derive(Serialize, Clone, Debug, TS)]
+#[ts(export, export_to = "../../src/messages/")]
+pub struct SkippedPath {
+    pub path: TreePath,
+    pub reason: String,
+}
+
+/// One revision of a reordered stack and the paths left conflicted in it
+#[derive(Serialize, Clone, Debug, TS)]
+#[ts(export, export_to = "../../src/messages/")]
+pub struct ReorderedRevision {
+    pub revision: RevHeader,
+    pub conflicted_paths: Vec<TreePath>,
+}
+
+/// What happened to one revision's bookmark in a stack push
+#[derive(Serialize, Clone, Debug, TS)]
+#[ts(export, export_to = "../../src/messages/")]
+pub struct RevisionPush {
+    pub revision: RevHeader,
+    pub branch_name: String,
+    pub outcome: PushOutcome,
+}
+
+#[derive(Serialize, Clone, Debug, TS)]
+#[serde(tag = "type")]
+#[ts(export, export_to = "../../src/messages/")]
+pub enum PushOutcome {
+    Pushed { action: PushAction },
+    UpToDate,
+    Rejected { reason: String },
 }
 
 /// Makes a revision the working copy
@@ -59,6 +124,15 @@ pub struct MoveRevision {
     pub parent_ids: Vec<RevId>,
 }
 
+/// Stacks revisions onto a base in the given order, bottom first, as a single operation.
+/// Descendants which aren't listed move to the top of the stack.
+#[derive(Deserialize, Debug, TS)]
+#[ts(export, export_to = "../../src/messages/")]
+pub struct ReorderRevisions {
+    pub base: RevId,
+    pub order: Vec<RevId>,
+}
+
 #[derive(Deserialize, Debug, TS)]
 #[ts(export, export_to = "../../src/messages/")]
 pub struct MoveSource {
@@ -84,6 +158,36 @@ pub struct CopyHunk {
     pub hunk: ChangeHunk,
 }
 
+/// Where a split's new revision goes, relative to the revision it was split from
+#[derive(Deserialize, Debug, TS)]
+#[ts(export, export_to = "../../src/messages/")]
+pub enum SplitPlacement {
+    /// The new revision becomes the original's parent
+    Before,
+    /// The new revision becomes the original's child, taking over its descendants
+    After,
+    /// The new revision shares the original's parents, and the original's children merge both
+    Sibling,
+}
+
+/// A hunk in one file
+#[derive(Deserialize, Debug, TS)]
+#[ts(export, export_to = "../../src/messages/")]
+pub struct FileHunk {
+    pub path: TreePath,
+    pub hunk: ChangeHunk,
+}
+
+/// Moves whole files and individual hunks out of a revision into a new revision
+#[derive(Deserialize, Debug, TS)]
+#[ts(export, export_to = "../../src/messages/")]
+pub struct SplitRevision {
+    pub id: RevId,
+    pub paths: Vec<TreePath>,
+    pub hunks: Vec<FileHunk>,
+    pub placement: SplitPlacement,
+}
+
 /// Updates a revision's description
 #[derive(Deserialize, Debug, TS)]
 #[ts(export, export_to = "../../src/messages/")]
@@ -122,6 +226,38 @@ pub struct MoveChanges {
     pub paths: Vec<TreePath>,
 }
 
+/// Moves each hunk of a revision into the closest mutable ancestor which last changed those lines,
+/// like `jj absorb`. An empty list of paths absorbs every file.
+#[derive(Deserialize, Debug, TS)]
+#[ts(export, export_to = "../../src/messages/")]
+pub struct AbsorbChanges {
+    pub from_id: RevId,
+    pub paths: Vec<TreePath>,
+}
+
+/// Which description a squash leaves on its destination
+#[derive(Deserialize, Debug, TS)]
+#[serde(tag = "type")]
+#[ts(export, export_to = "../../src/messages/")]
+pub enum SquashMessage {
+    Source,
+    Destination,
+    /// Both descriptions, destination first; empty ones are skipped
+    Combined,
+    Custom {
+        description: String,
+    },
+}
+
+/// Moves all of a revision's changes into another revision and abandons the emptied source
+#[derive(Deserialize, Debug, TS)]
+#[ts(export, export_to = "../../src/messages/")]
+pub struct SquashRevision {
+    pub from_id: RevId,
+    pub to_id: CommitId, // limitation: we don't know parent chids because they are more expensive to look up
+    pub message: SquashMessage,
+}
+
 #[derive(Deserialize, Debug, TS)]
 #[ts(export, export_to = "../../src/messages/")]
 pub struct CopyChanges {
@@ -183,6 +319,20 @@ pub enum GitPush {
         remote_name: String,
         branch_ref: StoreRef,
     },
+    /// Creates or moves a bookmark named after the revision's change, then pushes it
+    Revision {
+        id: RevId,
+        remote_name: String,
+    },
+}
+
+/// Pushes every mutable revision between trunk() and a head, each under a bookmark named after
+/// its change
+#[derive(Deserialize, Debug, TS)]
+#[ts(export, export_to = "../../src/messages/")]
+pub struct GitPushStack {
+    pub head_id: RevId,
+    pub remote_name: String,
 }
 
 #[derive(Deserialize, Debug, TS)]
@@ -201,6 +351,66 @@ pub enum GitFetch {
     },
 }
 
+/// Adds a git remote, optionally with a separate URL to push to
+#[derive(Deserialize, Debug, TS)]
+#[ts(export, export_to = "../../src/messages/")]
+pub struct AddRemote {
+    pub remote_name: String,
+    pub url: String,
+    pub push_url: Option<String>,
+}
+
+/// Removes a git remote along with its remote bookmarks
+#[derive(Deserialize, Debug, TS)]
+#[ts(export, export_to = "../../src/messages/")]
+pub struct RemoveRemote {
+    pub remote_name: String,
+}
+
+/// Renames a git remote, moving its remote bookmarks to the new name
+#[derive(Deserialize, Debug, TS)]
+#[ts(export, export_to = "../../src/messages/")]
+pub struct RenameRemote {
+    pub old_name: String,
+    pub new_name: String,
+}
+
+/// Changes the URL a git remote fetches from, and the one it pushes to if given
+#[derive(Deserialize, Debug, TS)]
+#[ts(export, export_to = "../../src/messages/")]
+pub struct SetRemoteUrl {
+    pub remote_name: String,
+    pub url: String,
+    pub push_url: Option<String>,
+}
+
 #[derive(Deserialize, Debug, TS)]
 #[ts(export, export_to = "../../src/messages/")]
 pub struct UndoOperation;
+
+/// Reapplies the operation most recently undone by gg
+#[derive(Deserialize, Debug, TS)]
+#[ts(export, export_to = "../../src/messages/")]
+pub struct RedoOperation;
+
+/// Resets the repo to the state recorded by an earlier operation
+#[derive(Deserialize, Debug, TS)]
+#[ts(export, export_to = "../../src/messages/")]
+pub struct RestoreOperation {
+    pub op_id: OperationId,
+}
+
+/// Makes a hidden commit visible again, either as a new change or in place of its change's current commit
+#[derive(Deserialize, Debug, TS)]
+#[ts(export, export_to = "../../src/messages/")]
+pub struct RestoreHiddenRevision {
+    pub id: RevId,
+    pub as_new_change: bool,
+}
+
+/// Applies the inverse of an earlier operation, keeping any operations which followed it
+#[derive(Deserialize, Debug, TS)]
+#[ts(export, export_to = "../../src/messages/")]
+pub struct RevertOperation {
+    pub op_id: OperationId,
+}
@@ -111,7 +111,7 @@ impl TryFrom<&Signature> for RevAuthor {
     }
 }
 
-#[derive(Serialize, Deserialize, Debug, TS)]
+#[derive(Serialize, Deserialize, Clone, Debug, TS)]
 #[ts(export, export_to = "../../src/messages/")]
 pub struct RevChange {
     pub kind: ChangeKind,
@@ -127,7 +127,7 @@ pub struct RevConflict {
     pub hunk: ChangeHunk,
 }
 
-#[derive(Serialize, Deserialize, Debug, TS)]
+#[derive(Serialize, Deserialize, Clone, Debug, TS)]
 #[ts(export, export_to = "../../src/messages/")]
 pub enum ChangeKind {
     None,
@@ -219,8 +219,123 @@ pub struct LogPage {
     pub has_more: bool,
 }
 
+/// An operation id with a short prefix for display
+#[derive(Serialize, Deserialize, Clone, Debug, TS)]
+#[ts(export, export_to = "../../src/messages/")]
+pub struct OperationId {
+    pub hex: String,
+    pub prefix: String,
+}
+
+#[derive(Serialize, Debug, TS)]
+#[ts(export, export_to = "../../src/messages/")]
+pub struct OperationHeader {
+    pub id: OperationId,
+    pub description: String,
+    pub username: String,
+    pub hostname: String,
+    pub time: chrono::DateTime<Local>,
+    pub tags: HashMap<String, String>,
+    pub is_snapshot: bool,
+    pub is_current: bool,
+}
+
+#[derive(Serialize, Debug, TS)]
+#[ts(export, export_to = "../../src/messages/")]
+pub struct OperationPage {
+    pub operations: Vec<OperationHeader>,
+    pub has_more: bool,
+}
+
+/// A revision which replaced one or more commits visible before the operation
+#[derive(Serialize, Debug, TS)]
+#[ts(export, export_to = "../../src/messages/")]
+pub struct RewrittenRevision {
+    pub header: RevHeader,
+    pub old_ids: Vec<CommitId>,
+}
+
+/// A ref whose targets differ between two operations
+#[derive(Serialize, Debug, TS)]
+#[ts(export, export_to = "../../src/messages/")]
+pub struct RefDiff {
+    pub r#ref: StoreRef,
+    pub old_targets: Vec<CommitId>,
+    pub new_targets: Vec<CommitId>,
+}
+
+/// Changes to the visible revisions and refs between two operations
+#[derive(Serialize, Debug, TS)]
+#[ts(export, export_to = "../../src/messages/")]
+pub struct OperationDiff {
+    pub from_op: OperationId,
+    pub to_op: OperationId,
+    pub added: Vec<RevHeader>,
+    pub rewritten: Vec<RewrittenRevision>,
+    pub abandoned: Vec<RevHeader>,
+    pub refs: Vec<RefDiff>,
+}
+
+/// A commit which a change has pointed to, with the operation that created it
+#[derive(Serialize, Debug, TS)]
+#[ts(export, export_to = "../../src/messages/")]
+pub struct EvolutionEntry {
+    pub header: RevHeader,
+    pub operation: Option<OperationHeader>,
+    pub predecessor_ids: Vec<CommitId>,
+    /// Relative to the predecessors, rebased onto this commit's parents
+    pub changes: Vec<RevChange>,
+}
+
+#[derive(Serialize, Debug, TS)]
+#[serde(tag = "type")]
+#[ts(export, export_to = "../../src/messages/")]
+pub enum EvolutionResult {
+    NotFound { id: RevId },
+    Detail { entries: Vec<EvolutionEntry> },
+}
+
+#[derive(Serialize, Clone, Debug, TS)]
+#[ts(export, export_to = "../../src/messages/")]
+pub enum PushAction {
+    Create,
+    Move,
+    /// The remote bookmark's commit is not an ancestor of the new target
+    ForceMove,
+    Delete,
+}
+
+/// An update to one bookmark on a remote
+#[derive(Serialize, Debug, TS)]
+#[ts(export, export_to = "../../src/messages/")]
+pub struct BookmarkPush {
+    pub branch_name: String,
+    pub action: PushAction,
+    pub old_target: Option<RevHeader>,
+    pub new_target: Option<RevHeader>,
+}
+
+/// Everything a push would send to one remote
+#[derive(Serialize, Debug, TS)]
+#[ts(export, export_to = "../../src/messages/")]
+pub struct RemotePush {
+    pub remote_name: String,
+    pub bookmarks: Vec<BookmarkPush>,
+    /// Commits not yet reachable from any of the remote's bookmarks
+    pub new_commits: Vec<RevHeader>,
+    pub has_more_commits: bool,
+}
+
+#[derive(Serialize, Debug, TS)]
+#[serde(tag = "type")]
+#[ts(export, export_to = "../../src/messages/")]
+pub enum PushPlan {
+    Blocked { message: String },
+    Ready { remotes: Vec<RemotePush> },
+}
+
 // similar to time_util::datetime_from_timestamp, which is not pub
-fn format_timestamp(context: &Timestamp) -> Result<DateTime<FixedOffset>> {
+pub(crate) fn format_timestamp(context: &Timestamp) -> Result<DateTime<FixedOffset>> {
     let utc = match Utc.timestamp_opt(
         context.timestamp.0.div_euclid(1000),
         (context.timestamp.0.rem_euclid(1000)) as u32 * 1000000,
@@ -1,50 +1,77 @@
 //! Analogous to cli_util from jj-cli
 //! We reuse a bit of jj-cli code, but many of its modules include TUI concerns or are not suitable for a long-running server
 
-use std::cell::OnceCell;
-use std::collections::HashMap;
-use std::ffi::OsStr;
-#[cfg(windows)]
-use std::os::windows::process::CommandExt;
+use std::cell::{Cell, OnceCell};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::panic::AssertUnwindSafe;
 use std::path::Path;
-use std::process::Command;
 use std::slice;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
 use std::{rc::Rc, sync::Arc};
 
 use anyhow::{Context, Result, anyhow};
 use chrono::TimeZone;
+use futures_util::FutureExt;
 use itertools::Itertools;
 use jj_cli::cli_util::default_ignored_remote_name;
 use jj_cli::git_util::is_colocated_git_workspace;
 use jj_cli::revset_util;
 use jj_lib::backend::{BackendError, ChangeId, CommitId};
 use jj_lib::commit::Commit;
+use jj_lib::config::{ConfigFile, ConfigSource};
 use jj_lib::default_index::DefaultReadonlyIndex;
 use jj_lib::file_util;
-use jj_lib::git::{self, REMOTE_NAME_FOR_LOCAL_GIT_REPO};
+use jj_lib::fileset::{self, FilesetDiagnostics};
+use jj_lib::git::{self, GitImportOptions, REMOTE_NAME_FOR_LOCAL_GIT_REPO};
 use jj_lib::git_backend::GitBackend;
+use jj_lib::gitignore::GitIgnoreFile;
 use jj_lib::id_prefix::{IdPrefixContext, IdPrefixIndex};
+use jj_lib::matchers::NothingMatcher;
 use jj_lib::object_id::ObjectId;
 use jj_lib::op_heads_store;
+use jj_lib::op_store::{OperationId, RefTarget};
+use jj_lib::op_walk;
 use jj_lib::operation::Operation;
-use jj_lib::ref_name::WorkspaceName;
-use jj_lib::repo::{ReadonlyRepo, Repo, RepoLoaderError, StoreFactories};
+use jj_lib::ref_name::{RefName, RefNameBuf, RemoteName, WorkspaceName};
+use jj_lib::refs::{
+    BookmarkPushAction, BookmarkPushUpdate, LocalAndRemoteRef, classify_bookmark_push_action,
+};
+use jj_lib::repo::{MutableRepo, ReadonlyRepo, Repo, RepoLoaderError, StoreFactories};
 use jj_lib::repo_path::{RepoPath, RepoPathUiConverter};
 use jj_lib::revset::{
     self, Revset, RevsetAliasesMap, RevsetDiagnostics, RevsetEvaluationError, RevsetExpression,
     RevsetExtensions, RevsetIteratorExt, RevsetParseContext, RevsetResolutionError,
     RevsetWorkspaceContext, SymbolResolverExtension, UserRevsetExpression,
 };
-use jj_lib::settings::UserSettings;
+use jj_lib::settings::{HumanByteSize, UserSettings};
+use jj_lib::str_util::{StringExpression, StringPattern};
 use jj_lib::transaction::Transaction;
 use jj_lib::view::View;
-use jj_lib::working_copy::CheckoutStats;
+use jj_lib::working_copy::{
+    CheckoutStats, SnapshotOptions, SnapshotStats, UntrackedReason, WorkingCopyFreshness,
+};
 use jj_lib::workspace::{self, DefaultWorkspaceLoaderFactory, Workspace, WorkspaceLoaderFactory};
 use thiserror::Error;
 
-use super::WorkerSession;
+use super::{Cancelled, Mutation, ViewingPastOperation, WorkerSession};
 use crate::config::{GGSettings, read_config};
-use crate::messages::{self, RevId};
+use crate::messages::{self, ProgressEvent, RevId};
+
+/// same length as jj's default short_operation_hash()
+const SHORT_OPERATION_ID_LEN: usize = 12;
+
+/// same length as jj's default change_id.short()
+const SHORT_CHANGE_ID_LEN: usize = 12;
+
+/// how many files a snapshot scans between progress reports
+const SNAPSHOT_PROGRESS_INTERVAL: usize = 100;
+
+/// operation tags recorded by gg, alongside jj's own "args"
+pub const TAG_MUTATION: &str = "gg.mutation";
+pub const TAG_CHANGES: &str = "gg.changes";
+pub const TAG_VERSION: &str = "gg.version";
 
 /// jj-dependent state, available when a workspace is open
 pub struct WorkspaceSession<'a> {
@@ -58,6 +85,21 @@ pub struct WorkspaceSession<'a> {
     // operation-specific data, containing a repo view and derived extras
     operation: SessionOperation,
     is_colocated: bool,
+
+    // operations undone by gg, which can be redone until something else changes the repo
+    redo_stack: RedoStack,
+
+    // loaded at a past operation rather than the head; mutations are disallowed
+    is_readonly: bool,
+
+    // kind of the mutation being executed, if any, for tagging the operations it creates
+    mutation_kind: Option<&'static str>,
+
+    // new files the latest snapshot left untracked, reported with the status
+    oversized_files: Vec<messages::OversizedFile>,
+
+    // why the latest snapshot failed, if it did, reported with the status
+    snapshot_error: Option<String>,
 }
 
 pub struct WorkspaceData {
@@ -75,6 +117,13 @@ pub struct SessionOperation {
     prefix_context: IdPrefixContext,
 }
 
+/// undone operations, valid only while the repo is still at the operation created by the latest undo/redo
+#[derive(Default)]
+struct RedoStack {
+    head: Option<OperationId>,
+    targets: Vec<OperationId>,
+}
+
 #[derive(Debug, Error)]
 pub enum RevsetError {
     #[error(transparent)]
@@ -85,6 +134,21 @@ pub enum RevsetError {
     Other(#[from] anyhow::Error),
 }
 
+/// reasons the working copy could not be snapshotted, reported to the user rather than treated as bugs
+#[derive(Debug, Error)]
+pub enum SnapshotError {
+    #[error(
+        "The working copy is stale (not updated since operation {0}). Run `jj workspace update-stale` to update it."
+    )]
+    StaleWorkingCopy(String),
+    #[error(
+        "The working copy was updated by operation {0}, which diverges from the repo's head. Run `jj workspace update-stale` to reconcile them."
+    )]
+    SiblingOperation(String),
+    #[error("The working copy has been updated by operation {0}; reload the repository.")]
+    OutdatedRepo(String),
+}
+
 impl From<BackendError> for RevsetError {
     fn from(value: BackendError) -> Self {
         RevsetError::Other(anyhow!(value))
@@ -137,8 +201,151 @@ impl WorkerSession {
             is_large,
             operation,
             is_colocated,
+            redo_stack: RedoStack::default(),
+            is_readonly: false,
+            mutation_kind: None,
+            oversized_files: Vec::new(),
+            snapshot_error: None,
         })
     }
+
+    /// sets up a workspace in a directory which doesn't have one, ready to be loaded
+    pub fn create_workspace(&self, wd: &Path, source: &messages::WorkspaceSource) -> Result<()> {
+        let (settings, _) = read_config(None)?;
+        let wd_existed = wd.exists();
+        fs::create_dir_all(wd)?;
+
+        match source {
+            messages::WorkspaceSource::Init { colocate: false } => {
+                Workspace::init_internal_git(&settings, wd)?;
+            }
+            messages::WorkspaceSource::Init { colocate: true } => {
+                Workspace::init_colocated_git(&settings, wd)?;
+            }
+            messages::WorkspaceSource::Colocate => {
+                let git_dir = wd.join(".git");
+                if !git_dir.is_dir() {
+                    return Err(anyhow!("{} is not a git repository", wd.display()));
+                }
+                let (workspace, repo) = Workspace::init_external_git(&settings, wd, &git_dir)?;
+
+                let mut tx = repo.start_transaction();
+                git::import_head(tx.repo_mut())?;
+                git::import_refs(tx.repo_mut(), &git_import_options(&settings)?)?;
+
+                // the files on disk already match HEAD, so the working copy starts from there
+                let head_id = tx.repo().view().git_head().as_normal().cloned();
+                finish_new_workspace(workspace, tx, head_id, true, "import git repository")?;
+            }
+            messages::WorkspaceSource::Clone { source, colocate } => {
+                if wd.read_dir()?.next().is_some() {
+                    return Err(anyhow!("{} is not empty", wd.display()));
+                }
+
+                // like jj git clone, don't leave a half-made repo behind if the fetch fails
+                let result = self.clone_workspace(&settings, wd, source, *colocate);
+                if result.is_err() {
+                    let clean_up_dirs = || -> std::io::Result<()> {
+                        fs::remove_dir_all(wd.join(".jj"))?;
+                        if *colocate {
+                            fs::remove_dir_all(wd.join(".git"))?;
+                        }
+                        if !wd_existed {
+                            fs::remove_dir(wd)?;
+                        }
+                        Ok(())
+                    };
+                    if let Err(err) = clean_up_dirs() {
+                        log::warn!("Failed to clean up {}: {err}", wd.display());
+                    }
+                }
+                result?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// similar to jj git clone: fetches everything from the source, then starts work on top of its
+    /// default branch, which is tracked and becomes trunk()
+    fn clone_workspace(
+        &self,
+        settings: &UserSettings,
+        wd: &Path,
+        source: &str,
+        colocate: bool,
+    ) -> Result<()> {
+        let (workspace, repo) = if colocate {
+            Workspace::init_colocated_git(settings, wd)?
+        } else {
+            Workspace::init_internal_git(settings, wd)?
+        };
+
+        let mut tx = repo.start_transaction();
+        let remote_name = RemoteName::new("origin");
+        git::add_remote(
+            tx.repo_mut(),
+            remote_name,
+            source,
+            None,
+            gix::remote::fetch::Tags::Included,
+            &StringExpression::all(),
+        )?;
+
+        let git_settings = settings.git_settings()?;
+        let import_options = git_import_options(settings)?;
+        let default_branch = Cell::new(None);
+        self.callbacks.with_git(
+            tx.repo_mut(),
+            &format!("Cloning {source}"),
+            None,
+            &|repo, cb| {
+                let refspecs = git::expand_fetch_refspecs(remote_name, vec![StringPattern::all()])?;
+                let mut fetcher = git::GitFetch::new(
+                    repo,
+                    git_settings.to_subprocess_options(),
+                    &import_options,
+                )?;
+                fetcher.fetch(remote_name, refspecs, cb, None, None)?;
+                fetcher.import_refs()?;
+                default_branch.set(fetcher.get_default_branch(remote_name)?);
+                Ok(())
+            },
+        )?;
+
+        let mut trunk_id = None;
+        if let Some(name) = default_branch.take() {
+            let symbol = name.to_remote_symbol(remote_name);
+            if let Some(commit_id) = tx
+                .repo()
+                .view()
+                .get_remote_bookmark(symbol)
+                .target
+                .as_normal()
+                .cloned()
+            {
+                let mut file = ConfigFile::load_or_empty(
+                    ConfigSource::Repo,
+                    workspace.repo_path().join("config.toml"),
+                )?;
+                file.set_value(["revset-aliases", "trunk()"], symbol.to_string())?;
+                file.save()?;
+
+                if settings.get_bool("git.track-default-bookmark-on-clone")? {
+                    tx.repo_mut().track_remote_bookmark(symbol)?;
+                }
+                trunk_id = Some(commit_id);
+            }
+        }
+
+        finish_new_workspace(
+            workspace,
+            tx,
+            trunk_id,
+            colocate,
+            format!("clone from {source}"),
+        )
+    }
 }
 
 impl WorkspaceSession<'_> {
@@ -169,7 +376,15 @@ impl WorkspaceSession<'_> {
             .map(|backend| backend.git_repo().to_owned())
     }
 
+    pub fn is_readonly(&self) -> bool {
+        self.is_readonly
+    }
+
     pub fn load_at_head(&mut self) -> Result<bool> {
+        if self.is_readonly {
+            return Ok(false);
+        }
+
         let head = load_at_head(&self.workspace, &self.data)?;
         if head.repo.op_id() != self.operation.repo.op_id() {
             self.operation = head;
@@ -179,6 +394,79 @@ impl WorkspaceSession<'_> {
         }
     }
 
+    /// switches to a historical, read-only view of the repo - or back to the head if `op_id` is None
+    pub fn load_at_operation(&mut self, op_id: Option<&messages::OperationId>) -> Result<()> {
+        match op_id {
+            Some(op_id) => {
+                let loader = self.workspace.repo_loader();
+                let op = op_walk::resolve_op_for_load(loader, &op_id.hex)?;
+                let repo = loader.load_at(&op).context("load operation")?;
+                if repo.view().get_wc_commit_id(self.name()).is_none() {
+                    return Err(anyhow!(
+                        "Workspace {} did not exist at operation {}",
+                        self.name().as_symbol(),
+                        op_id.prefix
+                    ));
+                }
+
+                self.operation = SessionOperation::new(self.name(), &self.data, repo);
+                self.is_readonly = true;
+            }
+            None => {
+                self.is_readonly = false;
+                self.operation = load_at_head(&self.workspace, &self.data)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// runs a mutation, tagging any operation it creates with its kind. panics are caught so that
+    /// the kind is always cleared afterwards; callers decide whether to report or resume them
+    pub async fn execute_mutation<M: Mutation + ?Sized>(
+        &mut self,
+        mutation: Box<M>,
+    ) -> thread::Result<Result<messages::MutationResult>> {
+        self.mutation_kind = Some(mutation.kind());
+        let result = AssertUnwindSafe(mutation.execute(self))
+            .catch_unwind()
+            .await;
+        self.mutation_kind = None;
+        result
+    }
+
+    /// fails with `Cancelled` if the UI has asked for the current operation to stop
+    pub fn check_cancelled(&self) -> Result<()> {
+        if self.session.cancellation.is_cancelled() {
+            Err(anyhow!(Cancelled))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// call after an undo, with the id of the operation which was current before undoing
+    pub fn push_redo(&mut self, undone_id: OperationId) {
+        if self.redo_stack.head.as_ref() != Some(&undone_id) {
+            self.redo_stack.targets.clear();
+        }
+        self.redo_stack.targets.push(undone_id);
+        self.redo_stack.head = Some(self.operation.repo.op_id().clone());
+    }
+
+    /// returns the operation to restore, if no other operation has happened since the last undo/redo
+    pub fn pop_redo(&mut self) -> Option<OperationId> {
+        if self.redo_stack.head.as_ref() != Some(self.operation.repo.op_id()) {
+            self.redo_stack = RedoStack::default();
+            return None;
+        }
+        self.redo_stack.targets.pop()
+    }
+
+    /// call after a redo, so that earlier undos remain redoable
+    pub fn keep_redo(&mut self) {
+        self.redo_stack.head = Some(self.operation.repo.op_id().clone());
+    }
+
     /***********************************************************/
     /* Functions for evaluating revset expressions             */
     /* unfortunately parse_context and resolver are not cached */
@@ -355,7 +643,6 @@ impl WorkspaceSession<'_> {
         Ok(commits)
     }
 
-    #[expect(unused)]
     pub fn resolve_multiple_commits(
         &self,
         ids: &[messages::CommitId],
@@ -366,7 +653,6 @@ impl WorkspaceSession<'_> {
     }
 
     // XXX ideally this would apply the same policy as resolve_single_change
-    #[expect(dead_code)]
     pub fn resolve_multiple_changes(
         &self,
         ids: impl IntoIterator<Item = RevId>,
@@ -456,6 +742,12 @@ impl WorkspaceSession<'_> {
                 .description
                 .clone(),
             working_copy: self.format_commit_id(&self.operation.wc_id),
+            historical_operation: self
+                .is_readonly
+                .then(|| self.format_operation_id(self.operation.repo.op_id())),
+            operation_tags: self.operation.repo.operation().metadata().tags.clone(),
+            oversized_files: self.oversized_files.clone(),
+            snapshot_error: self.snapshot_error.clone(),
         }
     }
 
@@ -522,6 +814,26 @@ impl WorkspaceSession<'_> {
         })
     }
 
+    pub fn format_operation_id(&self, id: &OperationId) -> messages::OperationId {
+        let hex = id.hex();
+        let prefix = hex[..SHORT_OPERATION_ID_LEN.min(hex.len())].to_owned();
+        messages::OperationId { hex, prefix }
+    }
+
+    pub fn format_operation(&self, op: &Operation) -> Result<messages::OperationHeader> {
+        let metadata = op.metadata();
+        Ok(messages::OperationHeader {
+            id: self.format_operation_id(op.id()),
+            description: metadata.description.clone(),
+            username: metadata.username.clone(),
+            hostname: metadata.hostname.clone(),
+            time: messages::format_timestamp(&metadata.time.end)?.with_timezone(&chrono::Local),
+            tags: metadata.tags.clone(),
+            is_snapshot: metadata.is_snapshot,
+            is_current: op.id() == self.operation.repo.op_id(),
+        })
+    }
+
     pub fn format_path<T: AsRef<RepoPath>>(&self, repo_path: T) -> Result<messages::TreePath> {
         let base_path = self.workspace.workspace_root();
         let relative_path =
@@ -554,11 +866,32 @@ impl WorkspaceSession<'_> {
      *********************************************************************/
 
     pub async fn start_transaction(&mut self) -> Result<Transaction> {
+        if self.is_readonly {
+            return Err(anyhow!(ViewingPastOperation));
+        }
+
         self.import_and_snapshot(true).await?;
         Ok(self.operation.repo.start_transaction())
     }
 
+    /// starts a transaction which is never committed, for previewing the effects of a mutation
+    pub fn start_preview_transaction(&self) -> Transaction {
+        self.operation.repo.start_transaction()
+    }
+
     pub fn finish_transaction(
+        &mut self,
+        tx: Transaction,
+        description: impl Into<String>,
+    ) -> Result<Option<messages::RepoStatus>> {
+        // a cancelled mutation's transaction is dropped rather than committed
+        self.check_cancelled()?;
+        self.commit_transaction(tx, description)
+    }
+
+    /// commits even if cancellation was requested; only for use once a remote has accepted a push or
+    /// served a fetch, which can't be undone. Everything else goes through finish_transaction.
+    pub fn commit_transaction(
         &mut self,
         mut tx: Transaction,
         description: impl Into<String>,
@@ -569,6 +902,8 @@ impl WorkspaceSession<'_> {
 
         tx.repo_mut().rebase_descendants()?;
 
+        tag_transaction(&mut tx, self.mutation_kind)?;
+
         let old_repo = tx.base_repo().clone();
 
         let maybe_old_wc_commit = old_repo
@@ -591,7 +926,7 @@ impl WorkspaceSession<'_> {
 
         self.operation = SessionOperation::new(self.name(), &self.data, tx.commit(description)?);
 
-        // XXX do this only if loaded at head, which is currently always true, but won't be once we have undo-redo
+        // transactions can't be started when loaded at a past operation, so this is the new head
         if let Some(new_commit) = &maybe_new_wc_commit {
             self.update_working_copy(maybe_old_wc_commit.as_ref(), new_commit)?;
         }
@@ -601,6 +936,13 @@ impl WorkspaceSession<'_> {
 
     // XXX does this need to do any operation merging in case of other writers?
     pub async fn import_and_snapshot(&mut self, force: bool) -> Result<bool> {
+        if self.is_readonly {
+            return Ok(false);
+        }
+
+        // other processes may have written operations since this one was loaded
+        let updated_head = self.load_at_head()?;
+
         if !(force
             || self
                 .data
@@ -608,15 +950,227 @@ impl WorkspaceSession<'_> {
                 .query_auto_snapshot()
                 .unwrap_or(!self.is_large))
         {
+            return Ok(updated_head);
+        }
+
+        // the total isn't known in advance, so progress is a count of the files scanned so far
+        self.report_snapshot_progress(false);
+        let result = self.import_and_snapshot_at_head().await;
+        self.report_snapshot_progress(true);
+
+        // a failed snapshot is reported with the status until one succeeds
+        match &result {
+            Ok(_) => self.snapshot_error = None,
+            Err(err) if err.is::<SnapshotError>() => self.snapshot_error = Some(err.to_string()),
+            Err(_) => (),
+        }
+
+        Ok(result? || updated_head)
+    }
+
+    pub fn snapshot_error(&self) -> Option<&str> {
+        self.snapshot_error.as_deref()
+    }
+
+    /// similar to cli_util's snapshot_working_copy, importing git HEAD and refs around the snapshot
+    async fn import_and_snapshot_at_head(&mut self) -> Result<bool> {
+        let mut updated = false;
+
+        if self.is_colocated {
+            updated |= self.import_git_head()?;
+        }
+
+        updated |= self.snapshot_working_copy().await?;
+
+        if self.operation.git_backend().is_some() {
+            let import_options = git_import_options(&self.data.workspace_settings)?;
+            let mut tx = self.operation.repo.start_transaction();
+            git::import_refs(tx.repo_mut(), &import_options)?;
+            if tx.repo().has_changes() {
+                tx.repo_mut().rebase_descendants()?;
+                tag_transaction(&mut tx, Some("ImportGitRefs"))?;
+                self.operation =
+                    SessionOperation::new(self.name(), &self.data, tx.commit("import git refs")?);
+                updated = true;
+            }
+        }
+
+        Ok(updated)
+    }
+
+    /// checks out git's HEAD if something else has moved it since the last import
+    fn import_git_head(&mut self) -> Result<bool> {
+        let mut tx = self.operation.repo.start_transaction();
+        git::import_head(tx.repo_mut())?;
+        if !tx.repo().has_changes() {
             return Ok(false);
         }
 
-        run_jj(["git", "import"])
-            .current_dir(self.workspace.workspace_root())
-            .output()
-            .expect("Failed to run jj git import");
+        let name = self.name().to_owned();
+        let mut locked_ws = None;
+        if let Some(new_git_head_id) = tx.repo().view().git_head().as_normal() {
+            let new_git_head_commit = tx.repo().store().get_commit(new_git_head_id)?;
+            let wc_commit = tx
+                .repo_mut()
+                .check_out(name.clone(), &new_git_head_commit)?;
+
+            // git has already updated the files, so only the working copy's state needs resetting
+            let mut locked = self.workspace.start_working_copy_mutation()?;
+            locked.locked_wc().reset(&wc_commit)?;
+            locked_ws = Some(locked);
+        }
+
+        tx.repo_mut().rebase_descendants()?;
+        tag_transaction(&mut tx, Some("ImportGitHead"))?;
+        self.operation = SessionOperation::new(&name, &self.data, tx.commit("import git head")?);
+        if let Some(locked_ws) = locked_ws {
+            locked_ws.finish(self.operation.repo.op_id().clone())?;
+        }
+
+        Ok(true)
+    }
+
+    /// records changes to the working copy's files in its commit
+    async fn snapshot_working_copy(&mut self) -> Result<bool> {
+        let name = self.name().to_owned();
+        let repo = self.operation.repo.clone();
+        let wc_commit = repo.store().get_commit(&self.operation.wc_id)?;
+
+        let settings = &self.data.workspace_settings;
+        let auto_track = settings.get_string("snapshot.auto-track")?;
+        let start_tracking_matcher = fileset::parse(
+            &mut FilesetDiagnostics::new(),
+            &auto_track,
+            &self.data.path_converter,
+        )
+        .context("parse snapshot.auto-track")?
+        .to_matcher();
+        let max_new_file_size = settings
+            .get::<HumanByteSize>("snapshot.max-new-file-size")?
+            .0;
+        // snapshots visit files from several threads, and can visit many thousands of them
+        let report_progress = self.session.callbacks.progress_reporter();
+        let files_scanned = AtomicUsize::new(0);
+        let progress = |_: &RepoPath| {
+            let count = files_scanned.fetch_add(1, Ordering::Relaxed) + 1;
+            if count % SNAPSHOT_PROGRESS_INTERVAL == 0 {
+                report_progress(snapshot_progress_event(Some(count), false));
+            }
+        };
+        let options = SnapshotOptions {
+            base_ignores: self.base_ignores()?,
+            progress: Some(&progress),
+            start_tracking_matcher: start_tracking_matcher.as_ref(),
+            force_tracking_matcher: &NothingMatcher,
+            max_new_file_size,
+        };
+
+        let mut locked_ws = self.workspace.start_working_copy_mutation()?;
+        let short_op_id = |id: &OperationId| id.hex()[..SHORT_OPERATION_ID_LEN].to_owned();
+        match WorkingCopyFreshness::check_stale(locked_ws.locked_wc(), &wc_commit, &repo)? {
+            WorkingCopyFreshness::Fresh => (),
+            WorkingCopyFreshness::Updated(wc_operation) => {
+                return Err(SnapshotError::OutdatedRepo(short_op_id(wc_operation.id())).into());
+            }
+            WorkingCopyFreshness::WorkingCopyStale => {
+                let wc_op_id = short_op_id(locked_ws.locked_wc().old_operation_id());
+                return Err(SnapshotError::StaleWorkingCopy(wc_op_id).into());
+            }
+            WorkingCopyFreshness::SiblingOperation => {
+                let wc_op_id = short_op_id(locked_ws.locked_wc().old_operation_id());
+                return Err(SnapshotError::SiblingOperation(wc_op_id).into());
+            }
+        }
+
+        let (new_tree, stats) = locked_ws
+            .locked_wc()
+            .snapshot(&options)
+            .await
+            .context("snapshot working copy")?;
+
+        // a new warning is reported even if the tree is unchanged
+        if new_tree.tree_ids() == wc_commit.tree_ids() {
+            drop(locked_ws);
+            return self.update_oversized_files(&stats);
+        }
+
+        let mut tx = repo.start_transaction();
+        tx.set_is_snapshot(true);
+        let new_wc_commit = tx
+            .repo_mut()
+            .rewrite_commit(&wc_commit)
+            .set_tree(new_tree)
+            .write()?;
+        tx.repo_mut()
+            .set_wc_commit(name.clone(), new_wc_commit.id().clone())?;
+        tx.repo_mut().rebase_descendants()?;
+        if self.is_colocated {
+            git::reset_head(tx.repo_mut(), &new_wc_commit)?;
+            git::export_refs(tx.repo_mut())?;
+        }
+        tag_transaction(&mut tx, Some("SnapshotWorkingCopy"))?;
+        self.operation =
+            SessionOperation::new(&name, &self.data, tx.commit("snapshot working copy")?);
+        locked_ws.finish(self.operation.repo.op_id().clone())?;
+        self.update_oversized_files(&stats)?;
+
+        Ok(true)
+    }
+
+    /// like jj, leaves new files which are too large untracked and warns about them; returns
+    /// whether the warning changed
+    fn update_oversized_files(&mut self, stats: &SnapshotStats) -> Result<bool> {
+        let mut oversized_files = Vec::new();
+        for (path, reason) in &stats.untracked_paths {
+            if let UntrackedReason::FileTooLarge { size, max_size } = reason {
+                oversized_files.push(messages::OversizedFile {
+                    path: self.format_path(path)?,
+                    size: *size,
+                    max_size: *max_size,
+                });
+            }
+        }
+
+        let changed = oversized_files.len() != self.oversized_files.len()
+            || oversized_files
+                .iter()
+                .zip(&self.oversized_files)
+                .any(|(new, old)| new.path.repo_path != old.path.repo_path || new.size != old.size);
+        self.oversized_files = oversized_files;
+        Ok(changed)
+    }
+
+    /// gitignores that apply outside the workspace: the user's global excludes file and, for git
+    /// repos, info/exclude
+    fn base_ignores(&self) -> Result<Arc<GitIgnoreFile>> {
+        let mut git_ignores = GitIgnoreFile::empty();
+
+        let excludes_file_path = match self.operation.git_backend() {
+            Some(git_backend) => git_backend
+                .git_repo()
+                .config_snapshot()
+                .trusted_path("core.excludesFile")
+                .and_then(Result::ok)
+                .map(|path| path.into_owned()),
+            None => None,
+        }
+        .or_else(|| dirs::config_dir().map(|dir| dir.join("git").join("ignore")));
+        if let Some(excludes_file_path) = excludes_file_path {
+            git_ignores = git_ignores.chain_with_file("", excludes_file_path)?;
+        }
+
+        if let Some(git_backend) = self.operation.git_backend() {
+            git_ignores = git_ignores
+                .chain_with_file("", git_backend.git_repo_path().join("info").join("exclude"))?;
+        }
+
+        Ok(git_ignores)
+    }
 
-        self.load_at_head()
+    fn report_snapshot_progress(&self, finished: bool) {
+        self.session
+            .callbacks
+            .report_progress(snapshot_progress_event(None, finished));
     }
 
     fn update_working_copy(
@@ -761,9 +1315,16 @@ impl RefIndex {
             &[]
         }
     }
+
+    /// every indexed ref, once per commit it targets
+    pub fn iter(&self) -> impl Iterator<Item = (&CommitId, &messages::StoreRef)> {
+        self.index
+            .iter()
+            .flat_map(|(id, refs)| refs.iter().map(move |r#ref| (id, r#ref)))
+    }
 }
 
-fn build_ref_index(repo: &ReadonlyRepo) -> RefIndex {
+pub fn build_ref_index(repo: &ReadonlyRepo) -> RefIndex {
     let potential_remotes = git::get_git_backend(repo.store())
         .ok()
         .map(|git_backend| git_backend.git_repo().remote_names().len())
@@ -870,6 +1431,97 @@ fn load_at_head(workspace: &Workspace, data: &WorkspaceData) -> Result<SessionOp
     ))
 }
 
+fn snapshot_progress_event(files_scanned: Option<usize>, finished: bool) -> ProgressEvent {
+    ProgressEvent {
+        operation: String::from("Snapshotting working copy"),
+        fraction: None,
+        detail: files_scanned.map(|count| format!("{count} files")),
+        cancel_id: None,
+        finished,
+    }
+}
+
+/// records what created an operation, the changes it touched and gg's version
+fn tag_transaction(tx: &mut Transaction, kind: Option<&str>) -> Result<()> {
+    let changes = changed_change_ids(tx)?
+        .iter()
+        .map(|id| id.reverse_hex())
+        .join(" ");
+    if let Some(kind) = kind {
+        tx.set_tag(TAG_MUTATION.to_owned(), kind.to_owned());
+    }
+    if !changes.is_empty() {
+        tx.set_tag(TAG_CHANGES.to_owned(), changes);
+    }
+    tx.set_tag(TAG_VERSION.to_owned(), env!("CARGO_PKG_VERSION").to_owned());
+    Ok(())
+}
+
+/// changes with commits which became visible or hidden during a transaction
+fn changed_change_ids(tx: &Transaction) -> Result<Vec<ChangeId>> {
+    let old_heads =
+        RevsetExpression::commits(tx.base_repo().view().heads().iter().cloned().collect());
+    let new_heads = RevsetExpression::commits(tx.repo().view().heads().iter().cloned().collect());
+    let changed = new_heads
+        .ancestors()
+        .minus(&old_heads.ancestors())
+        .union(&old_heads.ancestors().minus(&new_heads.ancestors()));
+
+    let commits: Vec<Commit> = changed
+        .evaluate(tx.repo())?
+        .iter()
+        .commits(tx.repo().store())
+        .try_collect()?;
+    Ok(commits
+        .iter()
+        .map(|commit| commit.change_id().clone())
+        .unique()
+        .collect())
+}
+
+/// commits the setup of a new workspace, starting the working copy on top of a commit if there is
+/// one, then writes out the working copy's files
+fn finish_new_workspace(
+    mut workspace: Workspace,
+    mut tx: Transaction,
+    parent_id: Option<CommitId>,
+    is_colocated: bool,
+    description: impl Into<String>,
+) -> Result<()> {
+    let workspace_name = workspace.workspace_name().to_owned();
+    if let Some(parent_id) = parent_id {
+        let parent = tx.repo().store().get_commit(&parent_id)?;
+        let wc_commit = tx.repo_mut().check_out(workspace_name.clone(), &parent)?;
+        if is_colocated {
+            git::reset_head(tx.repo_mut(), &wc_commit)?;
+        }
+    }
+    tx.repo_mut().rebase_descendants()?;
+    if is_colocated {
+        git::export_refs(tx.repo_mut())?;
+    }
+    let repo = tx.commit(description)?;
+
+    let wc_id = repo
+        .view()
+        .get_wc_commit_id(&workspace_name)
+        .ok_or(anyhow!("No working copy found for workspace"))?;
+    let wc_commit = repo.store().get_commit(wc_id)?;
+    workspace.check_out(repo.op_id().clone(), None, &wc_commit)?;
+
+    Ok(())
+}
+
+/// how fetches bring remote bookmarks into the repo
+pub fn git_import_options(settings: &UserSettings) -> Result<GitImportOptions> {
+    let git_settings = settings.git_settings()?;
+    Ok(GitImportOptions {
+        auto_local_bookmark: git_settings.auto_local_bookmark,
+        abandon_unreachable_commits: git_settings.abandon_unreachable_commits,
+        remote_auto_track_bookmarks: HashMap::new(),
+    })
+}
+
 /// Lists only fetchable remotes
 pub fn get_git_remote_names(git_repo: &gix::Repository) -> Vec<String> {
     git_repo
@@ -885,10 +1537,256 @@ pub fn get_git_remote_names(git_repo: &gix::Repository) -> Vec<String> {
         .collect()
 }
 
-pub fn run_jj(args: impl IntoIterator<Item = impl AsRef<OsStr>>) -> Command {
-    let mut command = Command::new("jj");
-    command.args(args);
-    #[cfg(windows)]
-    command.creation_flags(0x08000000);
-    command
+/// bookmark updates to send to each remote
+pub type PushUpdates = Vec<(String, Vec<(RefNameBuf, BookmarkPushUpdate)>)>;
+
+/// decides which bookmarks a push would update, following the rules of `jj git push`; returns
+/// a message instead if the push shouldn't happen. remotes with nothing to update are omitted.
+/// `repo` may differ from the session's repo by the bookmark a revision push creates
+pub fn plan_push(
+    ws: &WorkspaceSession,
+    repo: &dyn Repo,
+    git_repo: &gix::Repository,
+    push: &messages::GitPush,
+) -> Result<Result<PushUpdates, String>> {
+    let settings = &ws.data.workspace_settings;
+    let view = repo.view();
+    let mut remote_branch_updates: PushUpdates = Vec::new();
+
+    match push {
+        // like `jj git push --remote`, which pushes the bookmarks in remote_bookmarks(remote)..@
+        // and skips, with a warning, those it isn't allowed to create or delete
+        messages::GitPush::AllBookmarks { remote_name } => {
+            let targeted_expr = RevsetExpression::remote_bookmarks(
+                StringExpression::all(),
+                StringExpression::exact(remote_name),
+                None,
+            )
+            .range(&RevsetExpression::working_copy(ws.name().to_owned()))
+            .intersection(&RevsetExpression::bookmarks(StringExpression::all()));
+            let targeted: HashSet<CommitId> = ws
+                .evaluate_revset_expr(targeted_expr)?
+                .iter()
+                .try_collect()?;
+            let allow_new = settings.get_bool("git.push-new-bookmarks")?;
+
+            let mut branch_updates = Vec::new();
+            for (branch_name, targets) in view.local_remote_bookmarks(RemoteName::new(remote_name))
+            {
+                if !targets
+                    .local_target
+                    .added_ids()
+                    .any(|id| targeted.contains(id))
+                    || !(targets.remote_ref.is_tracked() || allow_new)
+                {
+                    continue;
+                }
+                if let Ok(Some(update)) =
+                    classify_branch_push(branch_name.as_str(), remote_name, targets)
+                {
+                    branch_updates.push((branch_name.to_owned(), update));
+                }
+            }
+            remote_branch_updates.push((remote_name.clone(), branch_updates));
+        }
+        messages::GitPush::AllRemotes { branch_ref } => {
+            let branch_name = RefName::new(branch_ref.as_branch()?);
+            let local_target = view.get_local_bookmark(branch_name);
+            for remote_name in get_git_remote_names(git_repo) {
+                let remote_ref = view.get_remote_bookmark(
+                    branch_name.to_remote_symbol(RemoteName::new(&remote_name)),
+                );
+                if !remote_ref.is_tracked() {
+                    continue;
+                }
+                let targets = LocalAndRemoteRef {
+                    local_target,
+                    remote_ref,
+                };
+                match classify_branch_push(branch_name.as_str(), &remote_name, targets) {
+                    Err(message) => return Ok(Err(message)),
+                    Ok(None) => (),
+                    Ok(Some(update)) => remote_branch_updates
+                        .push((remote_name, vec![(branch_name.to_owned(), update)])),
+                }
+            }
+        }
+        messages::GitPush::RemoteBookmark {
+            remote_name,
+            branch_ref,
+        } => {
+            let branch_name = RefName::new(branch_ref.as_branch()?);
+            match classify_single_push(view, remote_name, branch_name) {
+                Err(message) => return Ok(Err(message)),
+                Ok(None) => (),
+                Ok(Some(update)) => remote_branch_updates
+                    .push((remote_name.clone(), vec![(branch_name.to_owned(), update)])),
+            }
+        }
+        // the caller is responsible for creating the bookmark; see set_push_bookmark
+        messages::GitPush::Revision { id, remote_name } => {
+            // any commit of the change gives the same name, even if it's since been rewritten
+            let commit_id = CommitId::try_from_hex(&id.commit.hex).expect("frontend-validated id");
+            let commit = repo.store().get_commit(&commit_id)?;
+            let branch_name = push_bookmark_name(settings, commit.change_id());
+            match classify_single_push(view, remote_name, &branch_name) {
+                Err(message) => return Ok(Err(message)),
+                Ok(None) => (),
+                Ok(Some(update)) => {
+                    remote_branch_updates.push((remote_name.clone(), vec![(branch_name, update)]))
+                }
+            }
+        }
+    };
+
+    remote_branch_updates.retain(|(_, branch_updates)| !branch_updates.is_empty());
+
+    for (remote_name, branch_updates) in &remote_branch_updates {
+        if let Err(message) = check_commits_ready_to_push(ws, repo, remote_name, branch_updates)? {
+            return Ok(Err(message));
+        }
+    }
+
+    Ok(Ok(remote_branch_updates))
+}
+
+/// refuses to publish the commits which `jj git push` would: those with conflicts, without a
+/// description, author or committer, or matching `git.private-commits`
+pub fn check_commits_ready_to_push(
+    ws: &WorkspaceSession,
+    repo: &dyn Repo,
+    remote_name: &str,
+    branch_updates: &[(RefNameBuf, BookmarkPushUpdate)],
+) -> Result<Result<(), String>> {
+    let new_heads = branch_updates
+        .iter()
+        .filter_map(|(_, update)| update.new_target.clone())
+        .collect_vec();
+    let remote_heads = repo
+        .view()
+        .remote_bookmarks(RemoteName::new(remote_name))
+        .flat_map(|(_, remote_ref)| remote_ref.target.added_ids())
+        .cloned()
+        .collect_vec();
+    let mut diagnostics = RevsetDiagnostics::new();
+    let immutable_heads =
+        revset_util::parse_immutable_heads_expression(&mut diagnostics, &ws.parse_context())?;
+    let commits_to_push = RevsetExpression::commits(remote_heads)
+        .union(&immutable_heads)
+        .range(&RevsetExpression::commits(new_heads));
+
+    let private_str = ws
+        .data
+        .workspace_settings
+        .get_string("git.private-commits")?;
+    let private_revset = ws.evaluate_revset_str(&private_str)?;
+    let is_private = private_revset.containing_fn();
+
+    let is_unset = |name: &str, email: &str| {
+        name.is_empty()
+            || name == UserSettings::USER_NAME_PLACEHOLDER
+            || email.is_empty()
+            || email == UserSettings::USER_EMAIL_PLACEHOLDER
+    };
+    for commit in ws
+        .evaluate_revset_expr(commits_to_push)?
+        .iter()
+        .commits(repo.store())
+    {
+        let commit = commit?;
+        let mut reasons = Vec::new();
+        if commit.description().is_empty() {
+            reasons.push("it has no description");
+        }
+        if is_unset(&commit.author().name, &commit.author().email)
+            || is_unset(&commit.committer().name, &commit.committer().email)
+        {
+            reasons.push("it has no author and/or committer set");
+        }
+        if commit.has_conflict() {
+            reasons.push("it has conflicts");
+        }
+        if is_private(commit.id())? {
+            reasons.push("it is private");
+        }
+        if !reasons.is_empty() {
+            return Ok(Err(format!(
+                "Won't push revision {} since {}",
+                ws.format_change_id(commit.change_id()).prefix,
+                reasons.join(" and ")
+            )));
+        }
+    }
+
+    Ok(Ok(()))
+}
+
+/// names the bookmark used to push a revision, like `jj git push --change`
+pub fn push_bookmark_name(settings: &UserSettings, change_id: &ChangeId) -> RefNameBuf {
+    let change_hex = change_id.reverse_hex();
+    let short_len = SHORT_CHANGE_ID_LEN.min(change_hex.len());
+    format!(
+        "{}{}",
+        settings.git_push_bookmark_prefix(),
+        &change_hex[..short_len]
+    )
+    .into()
+}
+
+/// points the automatically-named bookmark for a commit's change at it, creating or moving it
+pub fn set_push_bookmark(repo: &mut MutableRepo, settings: &UserSettings, commit: &Commit) {
+    let branch_name = push_bookmark_name(settings, commit.change_id());
+    repo.set_local_bookmark_target(&branch_name, RefTarget::normal(commit.id().clone()));
+}
+
+/// decides what pushing a bookmark to a remote would do, if anything
+pub fn classify_single_push(
+    view: &View,
+    remote_name: &str,
+    branch_name: &RefName,
+) -> Result<Option<BookmarkPushUpdate>, String> {
+    let targets = LocalAndRemoteRef {
+        local_target: view.get_local_bookmark(branch_name),
+        remote_ref: view
+            .get_remote_bookmark(branch_name.to_remote_symbol(RemoteName::new(remote_name))),
+    };
+    classify_branch_push(branch_name.as_str(), remote_name, targets)
+}
+
+/// describes an update for display; moves which drop commits from the remote are forced
+pub fn classify_push_action(
+    repo: &dyn Repo,
+    update: &BookmarkPushUpdate,
+) -> Result<messages::PushAction> {
+    Ok(match (&update.old_target, &update.new_target) {
+        (None, _) => messages::PushAction::Create,
+        (Some(_), None) => messages::PushAction::Delete,
+        (Some(old_id), Some(new_id)) => {
+            if repo.index().is_ancestor(old_id, new_id)? {
+                messages::PushAction::Move
+            } else {
+                messages::PushAction::ForceMove
+            }
+        }
+    })
+}
+
+/// decides what pushing a bookmark to a remote would do; conflicted or untracked bookmarks are
+/// reported as messages rather than pushed
+fn classify_branch_push(
+    branch_name: &str,
+    remote_name: &str,
+    targets: LocalAndRemoteRef,
+) -> Result<Option<BookmarkPushUpdate>, String> {
+    match classify_bookmark_push_action(targets) {
+        BookmarkPushAction::Update(update) => Ok(Some(update)),
+        BookmarkPushAction::AlreadyMatches => Ok(None),
+        BookmarkPushAction::LocalConflicted => Err(format!("Bookmark {branch_name} is conflicted")),
+        BookmarkPushAction::RemoteConflicted => Err(format!(
+            "Bookmark {branch_name}@{remote_name} is conflicted; try fetching first"
+        )),
+        BookmarkPushAction::RemoteUntracked => Err(format!(
+            "Bookmark {branch_name}@{remote_name} exists but is not tracked"
+        )),
+    }
 }
@@ -10,9 +10,13 @@ mod tests;
 
 use std::{
     env::{self, VarError},
-    fmt::Debug,
+    fmt::{self, Debug, Display, Formatter},
     fs,
     path::PathBuf,
+    sync::{
+        Arc,
+        atomic::{AtomicU32, Ordering},
+    },
 };
 
 use anyhow::{Error, Result, anyhow};
@@ -29,6 +33,12 @@ pub trait Mutation: Debug {
         std::any::type_name::<Self>().to_owned()
     }
 
+    /// unqualified type name, recorded in the tags of operations created by the mutation
+    fn kind(&self) -> &'static str {
+        let name = std::any::type_name::<Self>();
+        name.rsplit("::").next().unwrap_or(name)
+    }
+
     async fn execute(
         self: Box<Self>,
         ws: &mut WorkspaceSession,
@@ -39,18 +49,29 @@ pub trait Mutation: Debug {
     where
         Self: Sized,
     {
-        Box::new(self).execute(ws).await
+        match ws.execute_mutation(Box::new(self)).await {
+            Ok(result) => result,
+            Err(panic) => std::panic::resume_unwind(panic),
+        }
     }
 }
 
 /// implemented by UI layers to request user input and receive progress
 pub trait WorkerCallbacks {
-    #[expect(unused)]
+    /// runs a git transfer; `operation` names it in progress reports, and `cancel_id` is given
+    /// if cancelling could still skip a later step, since the transfer itself can't be stopped
     fn with_git(
         &self,
         repo: &mut MutableRepo,
+        operation: &str,
+        cancel_id: Option<u32>,
         f: &dyn Fn(&mut MutableRepo, RemoteCallbacks<'_>) -> Result<()>,
     ) -> Result<()>;
+
+    fn report_progress(&self, progress: messages::ProgressEvent);
+
+    /// like report_progress, but usable from the threads which jj-lib fans work out to
+    fn progress_reporter(&self) -> Box<dyn Fn(messages::ProgressEvent) + Send + Sync>;
 }
 
 struct NoCallbacks;
@@ -59,30 +80,113 @@ impl WorkerCallbacks for NoCallbacks {
     fn with_git(
         &self,
         repo: &mut MutableRepo,
+        _operation: &str,
+        _cancel_id: Option<u32>,
         f: &dyn Fn(&mut MutableRepo, RemoteCallbacks<'_>) -> Result<()>,
     ) -> Result<()> {
         f(repo, RemoteCallbacks::default())
     }
+
+    fn report_progress(&self, _progress: messages::ProgressEvent) {}
+
+    fn progress_reporter(&self) -> Box<dyn Fn(messages::ProgressEvent) + Send + Sync> {
+        Box::new(|_| ())
+    }
+}
+
+/// shared between a worker and its UI so that the operation in progress can be stopped; each
+/// operation gets its own id, so a request meant for one can't stop another
+#[derive(Clone, Default)]
+pub struct CancellationToken(Arc<CancellationState>);
+
+#[derive(Default)]
+struct CancellationState {
+    current: AtomicU32,
+    cancelled: AtomicU32,
 }
 
+/// ids are unique across tokens, so a request can be passed to all of a window's workers
+static NEXT_OPERATION_ID: AtomicU32 = AtomicU32::new(1);
+
+impl CancellationToken {
+    /// starts a new operation, returning the id which cancels it
+    pub fn begin(&self) -> u32 {
+        let id = NEXT_OPERATION_ID.fetch_add(1, Ordering::SeqCst);
+        self.0.current.store(id, Ordering::SeqCst);
+        id
+    }
+
+    /// stops the operation with the given id, if it's still the one in progress
+    pub fn cancel(&self, id: u32) {
+        if self.0.current.load(Ordering::SeqCst) == id {
+            self.0.cancelled.store(id, Ordering::SeqCst);
+        }
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        let current = self.0.current.load(Ordering::SeqCst);
+        current != 0 && self.0.cancelled.load(Ordering::SeqCst) == current
+    }
+
+    /// the id of the operation in progress, reported to the UI with its progress
+    pub fn current_id(&self) -> u32 {
+        self.0.current.load(Ordering::SeqCst)
+    }
+}
+
+impl PartialEq for CancellationToken {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+/// error returned by work abandoned because of a cancellation request
+#[derive(Debug)]
+pub struct Cancelled;
+
+impl Display for Cancelled {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("Operation cancelled")
+    }
+}
+
+impl std::error::Error for Cancelled {}
+
+/// error returned by mutations attempted while the repo is loaded at a past operation
+#[derive(Debug)]
+pub struct ViewingPastOperation;
+
+impl Display for ViewingPastOperation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("Cannot modify the repo while viewing a past operation")
+    }
+}
+
+impl std::error::Error for ViewingPastOperation {}
+
 /// state that doesn't depend on jj-lib borrowings
 pub struct WorkerSession {
     pub force_log_page_size: Option<usize>,
     pub latest_query: Option<String>,
-    #[expect(unused)]
     pub callbacks: Box<dyn WorkerCallbacks>,
+    pub cancellation: CancellationToken,
     pub working_directory: Option<PathBuf>,
     pub user_settings: Option<UserSettings>,
 }
 
 impl WorkerSession {
-    pub fn new<T: WorkerCallbacks + 'static>(callbacks: T, workspace: Option<PathBuf>) -> Self {
+    pub fn new<T: WorkerCallbacks + 'static>(
+        callbacks: T,
+        cancellation: CancellationToken,
+        workspace: Option<PathBuf>,
+    ) -> Self {
         let user_settings = match read_config(None) {
             Ok((settings, _)) => Some(settings),
             Err(_) => None,
         };
         WorkerSession {
             callbacks: Box::new(callbacks),
+            cancellation,
             working_directory: workspace,
             user_settings,
             ..Default::default()
@@ -109,6 +213,7 @@ impl Default for WorkerSession {
             force_log_page_size: None,
             latest_query: None,
             callbacks: Box::new(NoCallbacks),
+            cancellation: CancellationToken::default(),
             working_directory: None,
             user_settings: None,
         }
@@ -1,32 +1,56 @@
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::slice;
 use std::sync::Arc;
 
 use anyhow::{Result, anyhow};
-use jj_lib::backend::{CopyId, FileId, TreeValue};
+use itertools::Itertools;
+use jj_cli::text_util::complete_newline;
+use jj_lib::absorb::{self, AbsorbSource};
+use jj_lib::backend::{CommitId, CopyId, FileId, TreeValue};
 use jj_lib::commit::Commit;
 use jj_lib::conflicts;
 use jj_lib::conflicts::{ConflictMarkerStyle, ConflictMaterializeOptions, MaterializedTreeValue};
 use jj_lib::files::FileMergeHunkLevel;
-use jj_lib::git::REMOTE_NAME_FOR_LOCAL_GIT_REPO;
+use jj_lib::git::{self, GitBranchPushTargets, REMOTE_NAME_FOR_LOCAL_GIT_REPO};
+use jj_lib::matchers::{EverythingMatcher, FilesMatcher, Matcher};
 use jj_lib::merge::{Merge, SameChange};
 use jj_lib::merged_tree::{MergedTree, MergedTreeBuilder};
 use jj_lib::object_id::ObjectId as ObjectIdTrait;
-use jj_lib::repo::Repo;
+use jj_lib::op_store::{OperationId, RefTarget, View};
+use jj_lib::op_walk;
+use jj_lib::operation::Operation;
+use jj_lib::ref_name::{RefName, RefNameBuf, RemoteName};
+use jj_lib::refs::BookmarkPushUpdate;
+use jj_lib::repo::{Repo, RepoLoader};
 use jj_lib::repo_path::RepoPath;
-use jj_lib::rewrite::{RebaseOptions, RebasedCommit};
+use jj_lib::revset::{
+    ResolvedRevsetExpression, RevsetExpression, RevsetIteratorExt, RevsetResolutionError,
+};
+use jj_lib::rewrite::{self, MoveCommitsLocation, MoveCommitsTarget, RebaseOptions, RebasedCommit};
 use jj_lib::store::Store;
-use jj_lib::str_util::StringPattern;
+use jj_lib::str_util::{StringExpression, StringPattern};
+use jj_lib::transaction::Transaction;
 use jj_lib::tree_merge::MergeOptions;
 use tokio::io::AsyncReadExt;
 
-use super::Mutation;
-use super::gui_util::WorkspaceSession;
+use super::gui_util::{
+    RevsetError, WorkspaceSession, check_commits_ready_to_push, classify_push_action,
+    classify_single_push, get_git_remote_names, git_import_options, plan_push, push_bookmark_name,
+    set_push_bookmark,
+};
+use super::queries::format_tree_changes;
+use super::{Cancelled, Mutation};
 use crate::messages::{
-    AbandonRevisions, BackoutRevisions, CheckoutRevision, CopyChanges, CopyHunk, CreateRef,
-    CreateRevision, CreateRevisionBetween, DeleteRef, DescribeRevision, DuplicateRevisions,
-    GitFetch, GitPush, Id, InsertRevision, MoveChanges, MoveHunk, MoveRef, MoveRevision,
-    MoveSource, MutationResult, RenameBranch, StoreRef, TrackBranch, UndoOperation, UntrackBranch,
+    AbandonRevisions, AbsorbChanges, AbsorbedChanges, AddRemote, BackoutRevisions,
+    CheckoutRevision, CopyChanges, CopyHunk, CreateRef, CreateRevision, CreateRevisionBetween,
+    DeleteRef, DescribeRevision, DuplicateRevisions, GitFetch, GitPush, GitPushStack, Id,
+    InsertRevision, MoveChanges, MoveHunk, MoveRef, MoveRevision, MoveSource, MutationResult,
+    PushOutcome, RedoOperation, RemoveRemote, RenameBranch, RenameRemote, ReorderRevisions,
+    ReorderedRevision, RestoreHiddenRevision, RestoreOperation, RevertOperation, RevisionPush,
+    SetRemoteUrl, SkippedPath, SplitPlacement, SplitRevision, SquashMessage, SquashRevision,
+    StoreRef, TrackBranch, TreePath, UndoOperation, UntrackBranch,
 };
-use crate::worker::gui_util::run_jj;
 
 macro_rules! precondition {
     ($($args:tt)*) => {
@@ -37,25 +61,24 @@ macro_rules! precondition {
 #[async_trait::async_trait(?Send)]
 impl Mutation for AbandonRevisions {
     async fn execute(self: Box<Self>, ws: &mut WorkspaceSession) -> Result<MutationResult> {
-        let result = run_jj(["abandon"])
-            .args(self.ids.iter().map(|id| id.multiple_of_four_prefix()))
-            .current_dir(ws.workspace.workspace_root())
-            .output();
-
-        match result {
-            Ok(output) => {
-                if output.status.success() {
-                    ws.load_at_head()?;
-                    Ok(MutationResult::Updated {
-                        new_status: ws.format_status(),
-                    })
-                } else {
-                    Ok(MutationResult::PreconditionError {
-                        message: String::from_utf8_lossy(&output.stderr).trim().into(),
-                    })
-                }
-            }
-            Err(e) => Err(anyhow!("Failed to execute jj abandon: {e}")),
+        let mut tx = ws.start_transaction().await?;
+
+        let abandoned = ws.resolve_multiple_commits(&self.ids)?;
+        if abandoned.is_empty() {
+            precondition!("No revisions to abandon");
+        }
+        if ws.check_immutable(abandoned.iter().map(|commit| commit.id().clone()))? {
+            precondition!("Some revisions are immutable");
+        }
+
+        for commit in &abandoned {
+            tx.repo_mut().record_abandoned_commit(commit);
+        }
+        tx.repo_mut().rebase_descendants()?;
+
+        match ws.finish_transaction(tx, format!("abandon {}", describe_commits(&abandoned)))? {
+            Some(new_status) => Ok(MutationResult::Updated { new_status }),
+            None => Ok(MutationResult::Unchanged),
         }
     }
 }
@@ -63,30 +86,37 @@ impl Mutation for AbandonRevisions {
 #[async_trait::async_trait(?Send)]
 impl Mutation for BackoutRevisions {
     async fn execute(self: Box<Self>, ws: &mut WorkspaceSession) -> Result<MutationResult> {
-        let result = run_jj(["revert"])
-            .args(
-                self.ids
-                    .iter()
-                    .flat_map(|id| ["-r".into(), id.change.multiple_of_four_prefix()]),
-            )
-            .args(["--onto", "@"])
-            .current_dir(ws.workspace.workspace_root())
-            .output();
-
-        match result {
-            Ok(output) => {
-                if output.status.success() {
-                    ws.load_at_head()?;
-                    Ok(MutationResult::Updated {
-                        new_status: ws.format_status(),
-                    })
-                } else {
-                    Ok(MutationResult::PreconditionError {
-                        message: String::from_utf8_lossy(&output.stderr).trim().into(),
-                    })
-                }
-            }
-            Err(e) => Err(anyhow!("Failed to execute jj revert: {e}")),
+        let mut tx = ws.start_transaction().await?;
+
+        let working_copy = ws.get_commit(ws.wc_id())?;
+        let reverted = ws.resolve_multiple_changes(self.ids)?;
+        if reverted.is_empty() {
+            precondition!("No revisions to back out");
+        }
+
+        // like `jj revert --onto @`: each reversal is a new commit stacked on the working copy,
+        // child-first so that it applies on top of its descendants' reversals
+        let mut parent_id = working_copy.id().clone();
+        let mut new_tree = working_copy.tree();
+        for commit in &reverted {
+            let parents: Vec<_> = commit.parents().try_collect()?;
+            let parent_tree = rewrite::merge_commit_trees(tx.repo(), &parents).await?;
+            new_tree = new_tree.merge(commit.tree(), parent_tree).await?;
+            let new_commit = tx
+                .repo_mut()
+                .new_commit(vec![parent_id], new_tree.clone())
+                .set_description(format!(
+                    "Revert \"{}\"\n\nThis reverts commit {}.\n",
+                    commit.description().lines().next().unwrap_or_default(),
+                    commit.id().hex()
+                ))
+                .write()?;
+            parent_id = new_commit.id().clone();
+        }
+
+        match ws.finish_transaction(tx, format!("revert {}", describe_commits(&reverted)))? {
+            Some(new_status) => Ok(MutationResult::Updated { new_status }),
+            None => Ok(MutationResult::Unchanged),
         }
     }
 }
@@ -94,27 +124,24 @@ impl Mutation for BackoutRevisions {
 #[async_trait::async_trait(?Send)]
 impl Mutation for CheckoutRevision {
     async fn execute(self: Box<Self>, ws: &mut WorkspaceSession) -> Result<MutationResult> {
-        let result = run_jj(["edit", &self.id.commit.multiple_of_four_prefix()])
-            .current_dir(ws.workspace.workspace_root())
-            .output();
-
-        match result {
-            Ok(output) => {
-                if output.status.success() {
-                    ws.load_at_head()?;
-                    let working_copy = ws.get_commit(ws.wc_id())?;
-                    let new_selection = ws.format_header(&working_copy, Some(false))?;
-                    Ok(MutationResult::UpdatedSelection {
-                        new_status: ws.format_status(),
-                        new_selection,
-                    })
-                } else {
-                    Ok(MutationResult::PreconditionError {
-                        message: String::from_utf8_lossy(&output.stderr).trim().into(),
-                    })
-                }
+        let mut tx = ws.start_transaction().await?;
+
+        let edited = ws.resolve_single_change(&self.id)?;
+        if ws.check_immutable(vec![edited.id().clone()])? {
+            precondition!("Revision is immutable");
+        }
+
+        tx.repo_mut().edit(ws.name().to_owned(), &edited)?;
+
+        match ws.finish_transaction(tx, format!("edit commit {}", edited.id().hex()))? {
+            Some(new_status) => {
+                let new_selection = ws.format_header(&edited, Some(false))?;
+                Ok(MutationResult::UpdatedSelection {
+                    new_status,
+                    new_selection,
+                })
             }
-            Err(e) => Err(anyhow!("Failed to execute jj edit: {e}")),
+            None => Ok(MutationResult::Unchanged),
         }
     }
 }
@@ -122,32 +149,36 @@ impl Mutation for CheckoutRevision {
 #[async_trait::async_trait(?Send)]
 impl Mutation for CreateRevision {
     async fn execute(self: Box<Self>, ws: &mut WorkspaceSession) -> Result<MutationResult> {
-        let result = run_jj(["new"])
-            .args(
-                self.parent_ids
-                    .iter()
-                    .map(|id| id.change.multiple_of_four_prefix()),
+        let mut tx = ws.start_transaction().await?;
+
+        let parents = self
+            .parent_ids
+            .iter()
+            .map(|id| ws.resolve_single_change(id))
+            .collect::<Result<Vec<_>, _>>()?;
+        if parents.is_empty() {
+            precondition!("No parent revisions");
+        }
+
+        let merged_tree = rewrite::merge_commit_trees(tx.repo(), &parents).await?;
+        let new_commit = tx
+            .repo_mut()
+            .new_commit(
+                parents.iter().map(|parent| parent.id().clone()).collect(),
+                merged_tree,
             )
-            .current_dir(ws.workspace.workspace_root())
-            .output();
-
-        match result {
-            Ok(output) => {
-                if output.status.success() {
-                    ws.load_at_head()?;
-                    let working_copy = ws.get_commit(ws.wc_id())?;
-                    let new_selection = ws.format_header(&working_copy, Some(false))?;
-                    Ok(MutationResult::UpdatedSelection {
-                        new_status: ws.format_status(),
-                        new_selection,
-                    })
-                } else {
-                    Ok(MutationResult::PreconditionError {
-                        message: String::from_utf8_lossy(&output.stderr).trim().into(),
-                    })
-                }
+            .write()?;
+        tx.repo_mut().edit(ws.name().to_owned(), &new_commit)?;
+
+        match ws.finish_transaction(tx, "new empty commit")? {
+            Some(new_status) => {
+                let new_selection = ws.format_header(&new_commit, Some(false))?;
+                Ok(MutationResult::UpdatedSelection {
+                    new_status,
+                    new_selection,
+                })
             }
-            Err(e) => Err(anyhow!("Failed to execute jj new: {e}")),
+            None => Ok(MutationResult::Unchanged),
         }
     }
 }
@@ -155,29 +186,43 @@ impl Mutation for CreateRevision {
 #[async_trait::async_trait(?Send)]
 impl Mutation for CreateRevisionBetween {
     async fn execute(self: Box<Self>, ws: &mut WorkspaceSession) -> Result<MutationResult> {
-        let result = run_jj(["new"])
-            .args(["-A", &self.after_id.multiple_of_four_prefix()])
-            .args(["-B", &self.before_id.change.multiple_of_four_prefix()])
-            .current_dir(ws.workspace.workspace_root())
-            .output();
-
-        match result {
-            Ok(output) => {
-                if output.status.success() {
-                    ws.load_at_head()?;
-                    let working_copy = ws.get_commit(ws.wc_id())?;
-                    let new_selection = ws.format_header(&working_copy, Some(false))?;
-                    Ok(MutationResult::UpdatedSelection {
-                        new_status: ws.format_status(),
-                        new_selection,
-                    })
-                } else {
-                    Ok(MutationResult::PreconditionError {
-                        message: String::from_utf8_lossy(&output.stderr).trim().into(),
-                    })
-                }
+        let mut tx = ws.start_transaction().await?;
+
+        let after = ws.resolve_single_commit(&self.after_id)?;
+        let before = ws.resolve_single_change(&self.before_id)?;
+        if ws.check_immutable(vec![before.id().clone()])? {
+            precondition!("Revision is immutable");
+        }
+        if tx.repo().index().is_ancestor(before.id(), after.id())? {
+            precondition!("Cannot create a revision before one of its own ancestors");
+        }
+
+        let new_commit = tx
+            .repo_mut()
+            .new_commit(vec![after.id().clone()], after.tree())
+            .write()?;
+
+        // like `jj new -A -B`, the new revision takes the place of `after` among the parents of `before`
+        let new_parent_ids = before
+            .parent_ids()
+            .iter()
+            .filter(|id| *id != after.id())
+            .cloned()
+            .chain([new_commit.id().clone()])
+            .collect();
+        rewrite::rebase_commit(tx.repo_mut(), before, new_parent_ids).await?;
+        tx.repo_mut().rebase_descendants()?;
+        tx.repo_mut().edit(ws.name().to_owned(), &new_commit)?;
+
+        match ws.finish_transaction(tx, "new empty commit")? {
+            Some(new_status) => {
+                let new_selection = ws.format_header(&new_commit, Some(false))?;
+                Ok(MutationResult::UpdatedSelection {
+                    new_status,
+                    new_selection,
+                })
             }
-            Err(e) => Err(anyhow!("Failed to execute jj new: {e}")),
+            None => Ok(MutationResult::Unchanged),
         }
     }
 }
@@ -185,25 +230,26 @@ impl Mutation for CreateRevisionBetween {
 #[async_trait::async_trait(?Send)]
 impl Mutation for DescribeRevision {
     async fn execute(self: Box<Self>, ws: &mut WorkspaceSession) -> Result<MutationResult> {
-        let result = run_jj(["describe", &self.id.change.multiple_of_four_prefix()])
-            .args(["-m", &self.new_description])
-            .current_dir(ws.workspace.workspace_root())
-            .output();
-
-        match result {
-            Ok(output) => {
-                if output.status.success() {
-                    ws.load_at_head()?;
-                    Ok(MutationResult::Updated {
-                        new_status: ws.format_status(),
-                    })
-                } else {
-                    Ok(MutationResult::PreconditionError {
-                        message: String::from_utf8_lossy(&output.stderr).trim().into(),
-                    })
-                }
-            }
-            Err(e) => Err(anyhow!("Failed to execute jj describe: {e}")),
+        let mut tx = ws.start_transaction().await?;
+
+        let described = ws.resolve_single_change(&self.id)?;
+        if ws.check_immutable(vec![described.id().clone()])? {
+            precondition!("Revision is immutable");
+        }
+        // same result as `jj describe -m`; reset_author is not yet supported
+        let new_description = complete_newline(self.new_description);
+        if new_description == described.description() {
+            return Ok(MutationResult::Unchanged);
+        }
+
+        tx.repo_mut()
+            .rewrite_commit(&described)
+            .set_description(new_description)
+            .write()?;
+
+        match ws.finish_transaction(tx, format!("describe commit {}", described.id().hex()))? {
+            Some(new_status) => Ok(MutationResult::Updated { new_status }),
+            None => Ok(MutationResult::Unchanged),
         }
     }
 }
@@ -211,29 +257,33 @@ impl Mutation for DescribeRevision {
 #[async_trait::async_trait(?Send)]
 impl Mutation for DuplicateRevisions {
     async fn execute(self: Box<Self>, ws: &mut WorkspaceSession) -> Result<MutationResult> {
-        let result = run_jj(["duplicate"])
-            .args(
-                self.ids
-                    .iter()
-                    .map(|id| id.change.multiple_of_four_prefix()),
-            )
-            .current_dir(ws.workspace.workspace_root())
-            .output();
-
-        match result {
-            Ok(output) => {
-                if output.status.success() {
-                    ws.load_at_head()?;
-                    Ok(MutationResult::Updated {
-                        new_status: ws.format_status(),
-                    })
-                } else {
-                    Ok(MutationResult::PreconditionError {
-                        message: String::from_utf8_lossy(&output.stderr).trim().into(),
+        let mut tx = ws.start_transaction().await?;
+
+        // revsets iterate child-first, which is the order duplication expects
+        let clonees = ws.resolve_multiple_changes(self.ids)?;
+        if clonees.is_empty() {
+            precondition!("No revisions to duplicate");
+        }
+        let clonee_ids = clonees
+            .iter()
+            .map(|commit| commit.id().clone())
+            .collect_vec();
+
+        let stats =
+            rewrite::duplicate_commits_onto_parents(tx.repo_mut(), &clonee_ids, &HashMap::new())?;
+
+        match ws.finish_transaction(tx, format!("duplicate {}", describe_commits(&clonees)))? {
+            Some(new_status) => match stats.duplicated_commits.values().exactly_one() {
+                Ok(new_commit) => {
+                    let new_selection = ws.format_header(new_commit, Some(false))?;
+                    Ok(MutationResult::UpdatedSelection {
+                        new_status,
+                        new_selection,
                     })
                 }
-            }
-            Err(e) => Err(anyhow!("Failed to execute jj duplicate: {e}")),
+                Err(_) => Ok(MutationResult::Updated { new_status }),
+            },
+            None => Ok(MutationResult::Unchanged),
         }
     }
 }
@@ -241,27 +291,40 @@ impl Mutation for DuplicateRevisions {
 #[async_trait::async_trait(?Send)]
 impl Mutation for InsertRevision {
     async fn execute(self: Box<Self>, ws: &mut WorkspaceSession) -> Result<MutationResult> {
-        let result = run_jj(["rebase"])
-            .args(["-r", &self.id.change.multiple_of_four_prefix()])
-            .args(["--after", &self.after_id.change.multiple_of_four_prefix()])
-            .args(["--before", &self.before_id.change.multiple_of_four_prefix()])
-            .current_dir(ws.workspace.workspace_root())
-            .output();
-
-        match result {
-            Ok(output) => {
-                if output.status.success() {
-                    ws.load_at_head()?;
-                    Ok(MutationResult::Updated {
-                        new_status: ws.format_status(),
-                    })
-                } else {
-                    Ok(MutationResult::PreconditionError {
-                        message: String::from_utf8_lossy(&output.stderr).trim().into(),
-                    })
-                }
-            }
-            Err(e) => Err(anyhow!("Failed to execute jj rebase: {e}")),
+        let mut tx = ws.start_transaction().await?;
+
+        let target = ws.resolve_single_change(&self.id)?;
+        let after = ws.resolve_single_change(&self.after_id)?;
+        let before = ws.resolve_single_change(&self.before_id)?;
+
+        if ws.check_immutable(vec![target.id().clone(), before.id().clone()])? {
+            precondition!("Revisions are immutable");
+        }
+        if target.id() == after.id() || target.id() == before.id() {
+            precondition!("Cannot insert a revision next to itself");
+        }
+        if tx.repo().index().is_ancestor(before.id(), after.id())? {
+            precondition!("Cannot insert a revision before one of its own ancestors");
+        }
+
+        rebase_revision(
+            &mut tx,
+            &target,
+            vec![after.id().clone()],
+            vec![before.id().clone()],
+        )?;
+
+        match ws.finish_transaction(
+            tx,
+            format!(
+                "insert commit {} between {} and {}",
+                target.id().hex(),
+                after.id().hex(),
+                before.id().hex()
+            ),
+        )? {
+            Some(new_status) => Ok(MutationResult::Updated { new_status }),
+            None => Ok(MutationResult::Unchanged),
         }
     }
 }
@@ -269,30 +332,144 @@ impl Mutation for InsertRevision {
 #[async_trait::async_trait(?Send)]
 impl Mutation for MoveRevision {
     async fn execute(self: Box<Self>, ws: &mut WorkspaceSession) -> Result<MutationResult> {
-        let result = run_jj(["rebase"])
-            .args(["-r", &self.id.change.multiple_of_four_prefix()])
-            .args(
-                self.parent_ids
-                    .iter()
-                    .flat_map(|id| ["-o".into(), id.change.multiple_of_four_prefix()]),
-            )
-            .current_dir(ws.workspace.workspace_root())
-            .output();
-
-        match result {
-            Ok(output) => {
-                if output.status.success() {
-                    ws.load_at_head()?;
-                    Ok(MutationResult::Updated {
-                        new_status: ws.format_status(),
-                    })
+        let mut tx = ws.start_transaction().await?;
+
+        let target = ws.resolve_single_change(&self.id)?;
+        let parents = self
+            .parent_ids
+            .iter()
+            .map(|id| ws.resolve_single_change(id))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if ws.check_immutable(vec![target.id().clone()])? {
+            precondition!("Revision is immutable");
+        }
+        if parents.iter().any(|parent| parent.id() == target.id()) {
+            precondition!("Cannot rebase a revision onto itself");
+        }
+
+        rebase_revision(
+            &mut tx,
+            &target,
+            parents.iter().map(|parent| parent.id().clone()).collect(),
+            vec![],
+        )?;
+
+        match ws.finish_transaction(tx, format!("rebase commit {}", target.id().hex()))? {
+            Some(new_status) => Ok(MutationResult::Updated { new_status }),
+            None => Ok(MutationResult::Unchanged),
+        }
+    }
+}
+
+#[async_trait::async_trait(?Send)]
+impl Mutation for ReorderRevisions {
+    async fn execute(self: Box<Self>, ws: &mut WorkspaceSession) -> Result<MutationResult> {
+        let base = ws.resolve_single_change(&self.base)?;
+        let order = self
+            .order
+            .iter()
+            .map(|id| ws.resolve_single_change(id))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if order.is_empty() {
+            precondition!("No revisions to reorder");
+        }
+        if !order.iter().map(|commit| commit.id()).all_unique() {
+            precondition!("Revisions are listed more than once");
+        }
+        if ws.check_immutable(order.iter().map(|commit| commit.id().clone()))? {
+            precondition!("Revisions are immutable");
+        }
+
+        let mut tx = ws.start_transaction().await?;
+
+        for commit in &order {
+            if commit.id() == base.id() {
+                precondition!("Cannot reorder a revision onto itself");
+            }
+            if tx.repo().index().is_ancestor(commit.id(), base.id())? {
+                precondition!(
+                    "Cannot reorder revision {} onto one of its descendants",
+                    ws.format_change_id(commit.change_id()).prefix
+                );
+            }
+            if commit.parent_ids().len() != 1 {
+                precondition!("Cannot reorder a merge commit");
+            }
+        }
+
+        // descendants which weren't listed go on top of the stack rather than following their
+        // own parent, which would fork it
+        let listed_expr =
+            RevsetExpression::commits(order.iter().map(|commit| commit.id().clone()).collect());
+        let unlisted_children: Vec<Commit> = ws
+            .evaluate_revset_expr(listed_expr.children().minus(&listed_expr))?
+            .iter()
+            .commits(ws.repo().store())
+            .try_collect()?;
+        let listed_ids: HashSet<CommitId> =
+            order.iter().map(|commit| commit.id().clone()).collect();
+
+        // rebase each revision's own changes onto the one before it
+        let mut reordered = Vec::new();
+        let mut parent = base;
+        for commit in order {
+            let new_commit = if commit.parent_ids() == slice::from_ref(parent.id()) {
+                commit
+            } else {
+                let old_parents: Vec<_> = commit.parents().try_collect()?;
+                let new_tree = parent
+                    .tree()
+                    .merge(old_parents[0].tree(), commit.tree())
+                    .await?;
+                tx.repo_mut()
+                    .rewrite_commit(&commit)
+                    .set_parents(vec![parent.id().clone()])
+                    .set_tree(new_tree)
+                    .write()?
+            };
+            reordered.push(new_commit.clone());
+            parent = new_commit;
+        }
+
+        for child in unlisted_children {
+            let mut new_parent_ids = Vec::new();
+            for parent_id in child.parent_ids() {
+                let new_parent_id = if listed_ids.contains(parent_id) {
+                    parent.id()
                 } else {
-                    Ok(MutationResult::PreconditionError {
-                        message: String::from_utf8_lossy(&output.stderr).trim().into(),
-                    })
+                    parent_id
+                };
+                if !new_parent_ids.contains(new_parent_id) {
+                    new_parent_ids.push(new_parent_id.clone());
                 }
             }
-            Err(e) => Err(anyhow!("Failed to execute jj rebase: {e}")),
+            if new_parent_ids != child.parent_ids() {
+                rewrite::rebase_commit(tx.repo_mut(), child, new_parent_ids).await?;
+            }
+        }
+
+        tx.repo_mut().rebase_descendants()?;
+
+        let mut revisions = Vec::new();
+        for commit in &reordered {
+            let mut conflicted_paths = Vec::new();
+            for (path, _) in commit.tree().conflicts() {
+                conflicted_paths.push(ws.format_path(path)?);
+            }
+            revisions.push(ReorderedRevision {
+                revision: ws.format_header(commit, Some(false))?,
+                conflicted_paths,
+            });
+        }
+
+        match ws.finish_transaction(tx, format!("reorder {} commits", reordered.len()))? {
+            Some(new_status) => Ok(MutationResult::UpdatedReorder {
+                new_status,
+                revisions,
+            }),
+            None => Ok(MutationResult::Unchanged),
         }
     }
 }
@@ -300,30 +477,32 @@ impl Mutation for MoveRevision {
 #[async_trait::async_trait(?Send)]
 impl Mutation for MoveSource {
     async fn execute(self: Box<Self>, ws: &mut WorkspaceSession) -> Result<MutationResult> {
-        let result = run_jj(["rebase"])
-            .args(["-r", &self.id.change.multiple_of_four_prefix()])
-            .args(
-                self.parent_ids
-                    .iter()
-                    .flat_map(|id| ["-o".into(), id.multiple_of_four_prefix()]),
-            )
-            .current_dir(ws.workspace.workspace_root())
-            .output();
-
-        match result {
-            Ok(output) => {
-                if output.status.success() {
-                    ws.load_at_head()?;
-                    Ok(MutationResult::Updated {
-                        new_status: ws.format_status(),
-                    })
-                } else {
-                    Ok(MutationResult::PreconditionError {
-                        message: String::from_utf8_lossy(&output.stderr).trim().into(),
-                    })
-                }
-            }
-            Err(e) => Err(anyhow!("Failed to execute jj rebase: {e}")),
+        let mut tx = ws.start_transaction().await?;
+
+        let target = ws.resolve_single_change(&self.id)?;
+        let parents = self
+            .parent_ids
+            .iter()
+            .map(|id| ws.resolve_single_commit(id))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if ws.check_immutable(vec![target.id().clone()])? {
+            precondition!("Revision is immutable");
+        }
+        if parents.iter().any(|parent| parent.id() == target.id()) {
+            precondition!("Cannot rebase a revision onto itself");
+        }
+
+        rebase_revision(
+            &mut tx,
+            &target,
+            parents.iter().map(|parent| parent.id().clone()).collect(),
+            vec![],
+        )?;
+
+        match ws.finish_transaction(tx, format!("rebase commit {}", target.id().hex()))? {
+            Some(new_status) => Ok(MutationResult::Updated { new_status }),
+            None => Ok(MutationResult::Unchanged),
         }
     }
 }
@@ -331,27 +510,193 @@ impl Mutation for MoveSource {
 #[async_trait::async_trait(?Send)]
 impl Mutation for MoveChanges {
     async fn execute(self: Box<Self>, ws: &mut WorkspaceSession) -> Result<MutationResult> {
-        let result = run_jj(["squash"])
-            .args(["--from", &self.from_id.change.multiple_of_four_prefix()])
-            .args(["--into", &self.to_id.multiple_of_four_prefix()])
-            .args(self.paths.iter().map(|path| path.repo_path.clone()))
-            .current_dir(ws.workspace.workspace_root())
-            .output();
-
-        match result {
-            Ok(output) => {
-                if output.status.success() {
-                    ws.load_at_head()?;
-                    Ok(MutationResult::Updated {
-                        new_status: ws.format_status(),
-                    })
-                } else {
-                    Ok(MutationResult::PreconditionError {
-                        message: String::from_utf8_lossy(&output.stderr).trim().into(),
-                    })
+        let mut tx = ws.start_transaction().await?;
+
+        let from = ws.resolve_single_change(&self.from_id)?;
+        let to = ws.resolve_single_commit(&self.to_id)?;
+        if ws.check_immutable(vec![from.id().clone(), to.id().clone()])? {
+            precondition!("Revisions are immutable");
+        }
+        if from.id() == to.id() {
+            precondition!("Cannot move changes into the same revision");
+        }
+
+        // like `jj squash --from --into`: the selected paths move, anything else stays behind
+        let matcher = build_matcher(&self.paths)?;
+        let from_parents: Vec<_> = from.parents().try_collect()?;
+        let base_tree = rewrite::merge_commit_trees(tx.repo(), &from_parents).await?;
+        let from_tree = from.tree();
+        let moved_tree = rewrite::restore_tree(&from_tree, &base_tree, matcher.as_ref()).await?;
+        let remainder_tree =
+            rewrite::restore_tree(&base_tree, &from_tree, matcher.as_ref()).await?;
+
+        let abandon_source = remainder_tree.tree_ids() == base_tree.tree_ids();
+        let description = combine_messages(&from, &to, abandon_source);
+        move_diff(
+            &mut tx,
+            &from,
+            to.clone(),
+            &base_tree,
+            &moved_tree,
+            remainder_tree,
+            description,
+        )
+        .await?;
+
+        match ws.finish_transaction(
+            tx,
+            format!(
+                "move changes from {} into {}",
+                from.id().hex(),
+                to.id().hex()
+            ),
+        )? {
+            Some(new_status) => Ok(MutationResult::Updated { new_status }),
+            None => Ok(MutationResult::Unchanged),
+        }
+    }
+}
+
+#[async_trait::async_trait(?Send)]
+impl Mutation for SquashRevision {
+    async fn execute(self: Box<Self>, ws: &mut WorkspaceSession) -> Result<MutationResult> {
+        let from = ws.resolve_single_change(&self.from_id)?;
+        let to = ws.resolve_single_commit(&self.to_id)?;
+
+        if ws.check_immutable(vec![from.id().clone(), to.id().clone()])? {
+            precondition!("Revisions are immutable");
+        }
+        if from.id() == to.id() {
+            precondition!("Cannot squash a revision into itself");
+        }
+
+        let mut tx = ws.start_transaction().await?;
+
+        let from_parents: Vec<_> = from.parents().try_collect()?;
+        if from_parents.len() != 1 {
+            precondition!("Cannot squash a merge commit");
+        }
+
+        // like MoveHunk, with the whole of the source as the sibling and nothing left behind
+        let base_tree = from_parents[0].tree();
+        let from_tree = from.tree();
+        let description = match self.message {
+            SquashMessage::Source => from.description().to_owned(),
+            SquashMessage::Destination => to.description().to_owned(),
+            SquashMessage::Combined => combine_messages(&from, &to, true),
+            SquashMessage::Custom { description } => description,
+        };
+        move_diff(
+            &mut tx,
+            &from,
+            to.clone(),
+            &base_tree,
+            &from_tree,
+            base_tree.clone(),
+            description,
+        )
+        .await?;
+
+        match ws.finish_transaction(
+            tx,
+            format!("squash commit {} into {}", from.id().hex(), to.id().hex()),
+        )? {
+            Some(new_status) => Ok(MutationResult::Updated { new_status }),
+            None => Ok(MutationResult::Unchanged),
+        }
+    }
+}
+
+#[async_trait::async_trait(?Send)]
+impl Mutation for AbsorbChanges {
+    async fn execute(self: Box<Self>, ws: &mut WorkspaceSession) -> Result<MutationResult> {
+        let from = ws.resolve_single_change(&self.from_id)?;
+
+        if ws.check_immutable(vec![from.id().clone()])? {
+            precondition!("Revision is immutable");
+        }
+
+        let mut tx = ws.start_transaction().await?;
+
+        // like `jj absorb --into mutable()`, though only ancestors can receive hunks
+        let destination_ids: Vec<CommitId> = ws
+            .evaluate_revset_str(&format!("::{0} & mutable() ~ {0}", from.id().hex()))?
+            .iter()
+            .try_collect()?;
+        if destination_ids.is_empty() {
+            precondition!("Revision has no mutable ancestors");
+        }
+        let destinations = ResolvedRevsetExpression::commits(destination_ids);
+
+        let matcher = build_matcher(&self.paths)?;
+
+        let source = AbsorbSource::from_commit(tx.repo(), from.clone()).await?;
+        let selected_trees =
+            absorb::split_hunks_to_trees(tx.repo(), &source, &destinations, matcher.as_ref())
+                .await?;
+
+        let skipped_paths = selected_trees
+            .skipped_paths
+            .iter()
+            .map(|(path, reason)| {
+                Ok(SkippedPath {
+                    path: ws.format_path(path)?,
+                    reason: reason.clone(),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        if selected_trees.target_commits.is_empty() {
+            precondition!("No changes could be absorbed into a mutable ancestor");
+        }
+
+        // remember the destinations' old commits so that their received hunks can be listed afterwards
+        let old_destinations = selected_trees
+            .target_commits
+            .keys()
+            .map(|id| {
+                let commit = tx.repo().store().get_commit(id)?;
+                Ok((commit.change_id().clone(), commit))
+            })
+            .collect::<Result<HashMap<_, _>>>()?;
+
+        let stats = absorb::absorb_hunks(tx.repo_mut(), &source, selected_trees.target_commits)?;
+
+        match ws.finish_transaction(
+            tx,
+            format!("absorb changes from commit {}", from.id().hex()),
+        )? {
+            Some(new_status) => {
+                let mut absorbed = Vec::new();
+                for new_commit in &stats.rewritten_destinations {
+                    let Some(old_commit) = old_destinations.get(new_commit.change_id()) else {
+                        continue;
+                    };
+
+                    // diff against the old commit rebased onto the new one's parents, which
+                    // may themselves have received hunks
+                    let old_tree = rewrite::rebase_to_dest_parent(
+                        ws.repo(),
+                        slice::from_ref(old_commit),
+                        new_commit,
+                    )
+                    .await?;
+                    let mut changes = Vec::new();
+                    let tree_diff = old_tree.diff_stream(&new_commit.tree(), &EverythingMatcher);
+                    format_tree_changes(ws, &mut changes, tree_diff).await?;
+
+                    absorbed.push(AbsorbedChanges {
+                        revision: ws.format_header(new_commit, Some(false))?,
+                        changes,
+                    });
                 }
+
+                Ok(MutationResult::UpdatedAbsorb {
+                    new_status,
+                    absorbed,
+                    skipped_paths,
+                })
             }
-            Err(e) => Err(anyhow!("Failed to execute jj squash: {e}")),
+            None => Ok(MutationResult::Unchanged),
         }
     }
 }
@@ -359,27 +704,102 @@ impl Mutation for MoveChanges {
 #[async_trait::async_trait(?Send)]
 impl Mutation for CopyChanges {
     async fn execute(self: Box<Self>, ws: &mut WorkspaceSession) -> Result<MutationResult> {
-        let result = run_jj(["restore"])
-            .args(["--from", &self.from_id.multiple_of_four_prefix()])
-            .args(["--into", &self.to_id.change.multiple_of_four_prefix()])
-            .args(self.paths.iter().map(|path| path.repo_path.clone()))
-            .current_dir(ws.workspace.workspace_root())
-            .output();
-
-        match result {
-            Ok(output) => {
-                if output.status.success() {
-                    ws.load_at_head()?;
-                    Ok(MutationResult::Updated {
-                        new_status: ws.format_status(),
-                    })
-                } else {
-                    Ok(MutationResult::PreconditionError {
-                        message: String::from_utf8_lossy(&output.stderr).trim().into(),
-                    })
+        let mut tx = ws.start_transaction().await?;
+
+        let from = ws.resolve_single_commit(&self.from_id)?;
+        let to = ws.resolve_single_change(&self.to_id)?;
+        if ws.check_immutable(vec![to.id().clone()])? {
+            precondition!("Revision is immutable");
+        }
+
+        // like `jj restore --from --into`, the selected paths are overwritten with the source's contents
+        let matcher = build_matcher(&self.paths)?;
+        let to_tree = to.tree();
+        let new_to_tree = rewrite::restore_tree(&from.tree(), &to_tree, matcher.as_ref()).await?;
+        if new_to_tree.tree_ids() == to_tree.tree_ids() {
+            return Ok(MutationResult::Unchanged);
+        }
+
+        tx.repo_mut()
+            .rewrite_commit(&to)
+            .set_tree(new_to_tree)
+            .write()?;
+
+        match ws.finish_transaction(
+            tx,
+            format!(
+                "restore into commit {} from {}",
+                to.id().hex(),
+                from.id().hex()
+            ),
+        )? {
+            Some(new_status) => Ok(MutationResult::Updated { new_status }),
+            None => Ok(MutationResult::Unchanged),
+        }
+    }
+}
+
+#[async_trait::async_trait(?Send)]
+impl Mutation for RestoreHiddenRevision {
+    async fn execute(self: Box<Self>, ws: &mut WorkspaceSession) -> Result<MutationResult> {
+        let mut tx = ws.start_transaction().await?;
+
+        let hidden = ws.resolve_single_commit(&self.id.commit)?;
+
+        // change ids only resolve to visible commits
+        let current: Vec<Commit> = match ws.evaluate_revset_str(&hidden.change_id().reverse_hex()) {
+            Ok(revset) => revset
+                .iter()
+                .commits(tx.base_repo().store())
+                .collect::<Result<_, _>>()?,
+            Err(RevsetError::Resolution(RevsetResolutionError::NoSuchRevision { .. })) => vec![],
+            Err(err) => return Err(err.into()),
+        };
+        if current.iter().any(|commit| commit.id() == hidden.id()) {
+            precondition!("Revision {} is not hidden", self.id.commit.prefix);
+        }
+
+        let restored = if self.as_new_change {
+            tx.repo_mut()
+                .rewrite_commit(&hidden)
+                .generate_new_change_id()
+                .write()?
+        } else {
+            match current.as_slice() {
+                [] => {
+                    tx.repo_mut().add_head(&hidden)?;
+                    hidden.clone()
                 }
+                [current] => {
+                    if ws.check_immutable(vec![current.id().clone()])? {
+                        precondition!("Revision {} is immutable", self.id.change.prefix);
+                    }
+
+                    // keep the change where it is now, with the hidden commit's content
+                    let tree = rewrite::rebase_to_dest_parent(
+                        tx.repo(),
+                        slice::from_ref(&hidden),
+                        current,
+                    )
+                    .await?;
+                    tx.repo_mut()
+                        .rewrite_commit(current)
+                        .set_tree(tree)
+                        .set_description(hidden.description())
+                        .set_author(hidden.author().clone())
+                        .set_predecessors(vec![current.id().clone(), hidden.id().clone()])
+                        .write()?
+                }
+                _ => precondition!("Change {} is divergent", self.id.change.prefix),
             }
-            Err(e) => Err(anyhow!("Failed to execute jj restore: {e}")),
+        };
+
+        match ws.finish_transaction(tx, format!("restore hidden commit {}", hidden.id().hex()))? {
+            Some(new_status) => Ok(MutationResult::UpdatedSelection {
+                new_status,
+                new_selection: ws.format_header(&restored, None)?,
+            }),
+            None => Ok(MutationResult::Unchanged),
         }
     }
 }
@@ -399,25 +819,26 @@ impl Mutation for TrackBranch {
                 remote_name,
                 ..
             } => {
-                let result = run_jj(["bookmark", "track"])
-                    .arg(format!("{}@{}", branch_name, remote_name))
-                    .current_dir(ws.workspace.workspace_root())
-                    .output();
-
-                match result {
-                    Ok(output) => {
-                        if output.status.success() {
-                            ws.load_at_head()?;
-                            Ok(MutationResult::Updated {
-                                new_status: ws.format_status(),
-                            })
-                        } else {
-                            Ok(MutationResult::PreconditionError {
-                                message: String::from_utf8_lossy(&output.stderr).trim().into(),
-                            })
-                        }
-                    }
-                    Err(e) => Err(anyhow!("Failed to execute jj bookmark track: {e}")),
+                let mut tx = ws.start_transaction().await?;
+
+                let symbol =
+                    RefName::new(&branch_name).to_remote_symbol(RemoteName::new(&remote_name));
+                let remote_ref = ws.view().get_remote_bookmark(symbol);
+                if remote_ref.is_absent() {
+                    precondition!("No such bookmark: {branch_name}@{remote_name}");
+                }
+                if remote_ref.is_tracked() {
+                    precondition!("{branch_name}@{remote_name} is already tracked");
+                }
+
+                tx.repo_mut().track_remote_bookmark(symbol)?;
+
+                match ws.finish_transaction(
+                    tx,
+                    format!("track remote bookmark {branch_name}@{remote_name}"),
+                )? {
+                    Some(new_status) => Ok(MutationResult::Updated { new_status }),
+                    None => Ok(MutationResult::Unchanged),
                 }
             }
         }
@@ -427,75 +848,46 @@ impl Mutation for TrackBranch {
 #[async_trait::async_trait(?Send)]
 impl Mutation for UntrackBranch {
     async fn execute(self: Box<Self>, ws: &mut WorkspaceSession) -> Result<MutationResult> {
-        match self.r#ref {
+        let mut tx = ws.start_transaction().await?;
+
+        let description = match self.r#ref {
             StoreRef::Tag { tag_name } => {
                 precondition!("{} is a tag and cannot be untracked", tag_name);
             }
             StoreRef::LocalBookmark { branch_name, .. } => {
-                for (remote_ref_symbol, remote_ref) in ws.view().remote_bookmarks_matching(
-                    &StringPattern::exact(branch_name).to_matcher(),
+                // untrack all remotes
+                for (symbol, remote_ref) in ws.view().remote_bookmarks_matching(
+                    &StringPattern::exact(&branch_name).to_matcher(),
                     &StringPattern::all().to_matcher(),
                 ) {
-                    if remote_ref_symbol.remote == REMOTE_NAME_FOR_LOCAL_GIT_REPO
-                        || !remote_ref.is_tracked()
-                    {
-                        continue;
-                    }
-
-                    let result = run_jj(["bookmark", "untrack"])
-                        .arg(format!(
-                            "{}@{}",
-                            remote_ref_symbol.name.as_str(),
-                            remote_ref_symbol.remote.as_str()
-                        ))
-                        .current_dir(ws.workspace.workspace_root())
-                        .output();
-
-                    match result {
-                        Ok(output) => {
-                            if !output.status.success() {
-                                return Ok(MutationResult::PreconditionError {
-                                    message: String::from_utf8_lossy(&output.stderr).trim().into(),
-                                });
-                            }
-                        }
-                        Err(e) => {
-                            return Err(anyhow!("Failed to execute jj bookmark untrack: {e}"));
-                        }
+                    if symbol.remote != REMOTE_NAME_FOR_LOCAL_GIT_REPO && remote_ref.is_tracked() {
+                        tx.repo_mut().untrack_remote_bookmark(symbol);
                     }
                 }
-
-                ws.load_at_head()?;
-                Ok(MutationResult::Updated {
-                    new_status: ws.format_status(),
-                })
+                format!("untrack remote bookmarks of {branch_name}")
             }
             StoreRef::RemoteBookmark {
                 branch_name,
                 remote_name,
                 ..
             } => {
-                let result = run_jj(["bookmark", "untrack"])
-                    .arg(format!("{}@{}", branch_name, remote_name))
-                    .current_dir(ws.workspace.workspace_root())
-                    .output();
-
-                match result {
-                    Ok(output) => {
-                        if output.status.success() {
-                            ws.load_at_head()?;
-                            Ok(MutationResult::Updated {
-                                new_status: ws.format_status(),
-                            })
-                        } else {
-                            Ok(MutationResult::PreconditionError {
-                                message: String::from_utf8_lossy(&output.stderr).trim().into(),
-                            })
-                        }
-                    }
-                    Err(e) => Err(anyhow!("Failed to execute jj bookmark untrack: {e}")),
+                let symbol =
+                    RefName::new(&branch_name).to_remote_symbol(RemoteName::new(&remote_name));
+                if remote_name == REMOTE_NAME_FOR_LOCAL_GIT_REPO.as_str() {
+                    precondition!("Git-tracking bookmarks cannot be untracked");
                 }
+                if !ws.view().get_remote_bookmark(symbol).is_tracked() {
+                    precondition!("{branch_name}@{remote_name} is not tracked");
+                }
+
+                tx.repo_mut().untrack_remote_bookmark(symbol);
+                format!("untrack remote bookmark {branch_name}@{remote_name}")
             }
+        };
+
+        match ws.finish_transaction(tx, description)? {
+            Some(new_status) => Ok(MutationResult::Updated { new_status }),
+            None => Ok(MutationResult::Unchanged),
         }
     }
 }
@@ -503,29 +895,34 @@ impl Mutation for UntrackBranch {
 #[async_trait::async_trait(?Send)]
 impl Mutation for RenameBranch {
     async fn execute(self: Box<Self>, ws: &mut WorkspaceSession) -> Result<MutationResult> {
-        let result = run_jj([
-            "bookmark",
-            "rename",
-            self.r#ref.as_branch()?,
-            &self.new_name,
-        ])
-        .current_dir(ws.workspace.workspace_root())
-        .output();
-
-        match result {
-            Ok(output) => {
-                if output.status.success() {
-                    ws.load_at_head()?;
-                    Ok(MutationResult::Updated {
-                        new_status: ws.format_status(),
-                    })
-                } else {
-                    Ok(MutationResult::PreconditionError {
-                        message: String::from_utf8_lossy(&output.stderr).trim().into(),
-                    })
-                }
-            }
-            Err(e) => Err(anyhow!("Failed to execute jj bookmark rename: {e}")),
+        let old_name = self.r#ref.as_branch()?;
+        let new_name = self.new_name.trim();
+        if new_name.is_empty() {
+            precondition!("Bookmark name cannot be empty");
+        }
+
+        let mut tx = ws.start_transaction().await?;
+
+        let target = ws.view().get_local_bookmark(RefName::new(old_name)).clone();
+        if target.is_absent() {
+            precondition!("No such bookmark: {old_name}");
+        }
+        if ws
+            .view()
+            .get_local_bookmark(RefName::new(new_name))
+            .is_present()
+        {
+            precondition!("Bookmark already exists: {new_name}");
+        }
+
+        tx.repo_mut()
+            .set_local_bookmark_target(RefName::new(new_name), target);
+        tx.repo_mut()
+            .set_local_bookmark_target(RefName::new(old_name), RefTarget::absent());
+
+        match ws.finish_transaction(tx, format!("rename bookmark {old_name} to {new_name}"))? {
+            Some(new_status) => Ok(MutationResult::Updated { new_status }),
+            None => Ok(MutationResult::Unchanged),
         }
     }
 }
@@ -533,9 +930,11 @@ impl Mutation for RenameBranch {
 #[async_trait::async_trait(?Send)]
 impl Mutation for CreateRef {
     async fn execute(self: Box<Self>, ws: &mut WorkspaceSession) -> Result<MutationResult> {
-        let revision_arg = self.id.change.multiple_of_four_prefix();
+        let mut tx = ws.start_transaction().await?;
 
-        match self.r#ref {
+        let commit = ws.resolve_single_change(&self.id)?;
+
+        let description = match self.r#ref {
             StoreRef::RemoteBookmark {
                 branch_name,
                 remote_name,
@@ -548,51 +947,45 @@ impl Mutation for CreateRef {
                 );
             }
             StoreRef::LocalBookmark { branch_name, .. } => {
-                let result = run_jj(["bookmark", "create"])
-                    .args(["-r", &revision_arg])
-                    .arg(&branch_name)
-                    .current_dir(ws.workspace.workspace_root())
-                    .output();
-
-                match result {
-                    Ok(output) => {
-                        if output.status.success() {
-                            ws.load_at_head()?;
-                            Ok(MutationResult::Updated {
-                                new_status: ws.format_status(),
-                            })
-                        } else {
-                            Ok(MutationResult::PreconditionError {
-                                message: String::from_utf8_lossy(&output.stderr).trim().into(),
-                            })
-                        }
-                    }
-                    Err(e) => Err(anyhow!("Failed to execute jj bookmark create: {e}")),
+                let name = RefName::new(branch_name.trim());
+                if name.as_str().is_empty() {
+                    precondition!("Bookmark name cannot be empty");
+                }
+                if ws.view().get_local_bookmark(name).is_present() {
+                    precondition!("Bookmark already exists: {}", name.as_str());
                 }
+
+                tx.repo_mut()
+                    .set_local_bookmark_target(name, RefTarget::normal(commit.id().clone()));
+                format!(
+                    "create bookmark {} pointing to commit {}",
+                    name.as_str(),
+                    commit.id().hex()
+                )
             }
             StoreRef::Tag { tag_name, .. } => {
-                let result = run_jj(["tag", "set"])
-                    .args(["-r", &revision_arg])
-                    .arg(&tag_name)
-                    .current_dir(ws.workspace.workspace_root())
-                    .output();
-
-                match result {
-                    Ok(output) => {
-                        if output.status.success() {
-                            ws.load_at_head()?;
-                            Ok(MutationResult::Updated {
-                                new_status: ws.format_status(),
-                            })
-                        } else {
-                            Ok(MutationResult::PreconditionError {
-                                message: String::from_utf8_lossy(&output.stderr).trim().into(),
-                            })
-                        }
-                    }
-                    Err(e) => Err(anyhow!("Failed to execute jj tag set: {e}")),
+                let name = RefName::new(tag_name.trim());
+                if name.as_str().is_empty() {
+                    precondition!("Tag name cannot be empty");
+                }
+                let existing = ws.view().get_local_tag(name);
+                if existing.is_present() && existing.as_normal() != Some(commit.id()) {
+                    precondition!("Tag already exists: {}", name.as_str());
                 }
+
+                tx.repo_mut()
+                    .set_local_tag_target(name, RefTarget::normal(commit.id().clone()));
+                format!(
+                    "create tag {} pointing to commit {}",
+                    name.as_str(),
+                    commit.id().hex()
+                )
             }
+        };
+
+        match ws.finish_transaction(tx, description)? {
+            Some(new_status) => Ok(MutationResult::Updated { new_status }),
+            None => Ok(MutationResult::Unchanged),
         }
     }
 }
@@ -600,70 +993,51 @@ impl Mutation for CreateRef {
 #[async_trait::async_trait(?Send)]
 impl Mutation for DeleteRef {
     async fn execute(self: Box<Self>, ws: &mut WorkspaceSession) -> Result<MutationResult> {
-        match self.r#ref {
-            StoreRef::RemoteBookmark { branch_name, .. } => {
-                let result = run_jj(["bookmark", "forget", &branch_name])
-                    .current_dir(ws.workspace.workspace_root())
-                    .output();
-
-                match result {
-                    Ok(output) => {
-                        if output.status.success() {
-                            ws.load_at_head()?;
-                            Ok(MutationResult::Updated {
-                                new_status: ws.format_status(),
-                            })
-                        } else {
-                            Ok(MutationResult::PreconditionError {
-                                message: String::from_utf8_lossy(&output.stderr).trim().into(),
-                            })
-                        }
-                    }
-                    Err(e) => Err(anyhow!("Failed to execute jj bookmark forget: {e}")),
+        let mut tx = ws.start_transaction().await?;
+
+        let description = match self.r#ref {
+            // forgetting removes the local bookmark and untracks its remotes, so that the
+            // deletion isn't pushed
+            StoreRef::RemoteBookmark { branch_name, .. }
+            | StoreRef::LocalBookmark { branch_name, .. } => {
+                let name = RefName::new(&branch_name);
+                let remote_symbols: Vec<_> = ws
+                    .view()
+                    .remote_bookmarks_matching(
+                        &StringPattern::exact(&branch_name).to_matcher(),
+                        &StringPattern::all().to_matcher(),
+                    )
+                    .filter(|(symbol, remote_ref)| {
+                        symbol.remote != REMOTE_NAME_FOR_LOCAL_GIT_REPO && remote_ref.is_tracked()
+                    })
+                    .map(|(symbol, _)| symbol)
+                    .collect();
+                if ws.view().get_local_bookmark(name).is_absent() && remote_symbols.is_empty() {
+                    precondition!("No such bookmark: {branch_name}");
                 }
-            }
-            StoreRef::LocalBookmark { branch_name, .. } => {
-                let result = run_jj(["bookmark", "forget", &branch_name])
-                    .current_dir(ws.workspace.workspace_root())
-                    .output();
-
-                match result {
-                    Ok(output) => {
-                        if output.status.success() {
-                            ws.load_at_head()?;
-                            Ok(MutationResult::Updated {
-                                new_status: ws.format_status(),
-                            })
-                        } else {
-                            Ok(MutationResult::PreconditionError {
-                                message: String::from_utf8_lossy(&output.stderr).trim().into(),
-                            })
-                        }
-                    }
-                    Err(e) => Err(anyhow!("Failed to execute jj bookmark forget: {e}")),
+
+                tx.repo_mut()
+                    .set_local_bookmark_target(name, RefTarget::absent());
+                for symbol in remote_symbols {
+                    tx.repo_mut().untrack_remote_bookmark(symbol);
                 }
+                format!("forget bookmark {branch_name}")
             }
             StoreRef::Tag { tag_name } => {
-                let result = run_jj(["tag", "delete", &tag_name])
-                    .current_dir(ws.workspace.workspace_root())
-                    .output();
-
-                match result {
-                    Ok(output) => {
-                        if output.status.success() {
-                            ws.load_at_head()?;
-                            Ok(MutationResult::Updated {
-                                new_status: ws.format_status(),
-                            })
-                        } else {
-                            Ok(MutationResult::PreconditionError {
-                                message: String::from_utf8_lossy(&output.stderr).trim().into(),
-                            })
-                        }
-                    }
-                    Err(e) => Err(anyhow!("Failed to execute jj tag delete: {e}")),
+                let name = RefName::new(&tag_name);
+                if ws.view().get_local_tag(name).is_absent() {
+                    precondition!("No such tag: {tag_name}");
                 }
+
+                tx.repo_mut()
+                    .set_local_tag_target(name, RefTarget::absent());
+                format!("delete tag {tag_name}")
             }
+        };
+
+        match ws.finish_transaction(tx, description)? {
+            Some(new_status) => Ok(MutationResult::Updated { new_status }),
+            None => Ok(MutationResult::Unchanged),
         }
     }
 }
@@ -672,9 +1046,11 @@ impl Mutation for DeleteRef {
 #[async_trait::async_trait(?Send)]
 impl Mutation for MoveRef {
     async fn execute(self: Box<Self>, ws: &mut WorkspaceSession) -> Result<MutationResult> {
-        let change_id_prefix = self.to_id.change.multiple_of_four_prefix();
+        let mut tx = ws.start_transaction().await?;
 
-        match self.r#ref {
+        let commit = ws.resolve_single_change(&self.to_id)?;
+
+        let description = match self.r#ref {
             StoreRef::RemoteBookmark {
                 branch_name,
                 remote_name,
@@ -683,52 +1059,33 @@ impl Mutation for MoveRef {
                 precondition!("Bookmark is remote: {branch_name}@{remote_name}")
             }
             StoreRef::LocalBookmark { branch_name, .. } => {
-                let result = run_jj(["bookmark"])
-                    .args(["move", &branch_name])
-                    .args(["--to", &change_id_prefix])
-                    .current_dir(ws.workspace.workspace_root())
-                    .output();
-
-                match result {
-                    Ok(output) => {
-                        if output.status.success() {
-                            ws.load_at_head()?;
-                            Ok(MutationResult::Updated {
-                                new_status: ws.format_status(),
-                            })
-                        } else {
-                            Ok(MutationResult::PreconditionError {
-                                message: String::from_utf8_lossy(&output.stderr).trim().into(),
-                            })
-                        }
-                    }
-                    Err(e) => Err(anyhow!("Failed to execute jj bookmark move: {e}")),
+                let name = RefName::new(&branch_name);
+                if ws.view().get_local_bookmark(name).is_absent() {
+                    precondition!("No such bookmark: {branch_name}");
                 }
+
+                tx.repo_mut()
+                    .set_local_bookmark_target(name, RefTarget::normal(commit.id().clone()));
+                format!(
+                    "point bookmark {branch_name} to commit {}",
+                    commit.id().hex()
+                )
             }
             StoreRef::Tag { tag_name } => {
-                let result = run_jj(["tag", "set"])
-                    .args(["-r", &change_id_prefix])
-                    .arg(&tag_name)
-                    .arg("--allow-move")
-                    .current_dir(ws.workspace.workspace_root())
-                    .output();
-
-                match result {
-                    Ok(output) => {
-                        if output.status.success() {
-                            ws.load_at_head()?;
-                            Ok(MutationResult::Updated {
-                                new_status: ws.format_status(),
-                            })
-                        } else {
-                            Ok(MutationResult::PreconditionError {
-                                message: String::from_utf8_lossy(&output.stderr).trim().into(),
-                            })
-                        }
-                    }
-                    Err(e) => Err(anyhow!("Failed to execute jj tag set: {e}")),
+                let name = RefName::new(&tag_name);
+                if ws.view().get_local_tag(name).is_absent() {
+                    precondition!("No such tag: {tag_name}");
                 }
+
+                tx.repo_mut()
+                    .set_local_tag_target(name, RefTarget::normal(commit.id().clone()));
+                format!("point tag {tag_name} to commit {}", commit.id().hex())
             }
+        };
+
+        match ws.finish_transaction(tx, description)? {
+            Some(new_status) => Ok(MutationResult::Updated { new_status }),
+            None => Ok(MutationResult::Unchanged),
         }
     }
 }
@@ -737,7 +1094,7 @@ impl Mutation for MoveRef {
 impl Mutation for MoveHunk {
     async fn execute(self: Box<Self>, ws: &mut WorkspaceSession) -> Result<MutationResult> {
         let from = ws.resolve_single_change(&self.from_id)?;
-        let mut to = ws.resolve_single_commit(&self.to_id)?;
+        let to = ws.resolve_single_commit(&self.to_id)?;
 
         if ws.check_immutable(vec![from.id().clone(), to.id().clone()])? {
             precondition!("Revisions are immutable");
@@ -762,23 +1119,12 @@ impl Mutation for MoveHunk {
         // Construct the "sibling tree": base_tree with just this hunk applied.
         // This represents a virtual sibling commit containing only the hunk.
         let store = tx.repo().store();
-        let base_content = read_file_content(store, &base_tree, repo_path).await?;
-        let sibling_content = apply_hunk_to_base(&base_content, &self.hunk)?;
-        let sibling_blob_id = store
-            .write_file(repo_path, &mut sibling_content.as_slice())
-            .await?;
-        let sibling_executable = match from_tree.path_value(repo_path)?.into_resolved() {
-            Ok(Some(TreeValue::File { executable, .. })) => executable,
-            Ok(_) => false,
-            Err(_) => false,
-        };
-        let sibling_tree = update_tree_entry(
-            store,
-            &base_tree,
-            repo_path,
-            sibling_blob_id,
-            sibling_executable,
-        )?;
+        let mut builder = MergedTreeBuilder::new(base_tree.clone());
+        builder.set_or_remove(
+            repo_path.to_owned(),
+            sibling_file_value(store, &base_tree, &from_tree, repo_path, vec![&self.hunk]).await?,
+        );
+        let sibling_tree = builder.write_tree()?;
 
         // Remove hunk from source: backout the base→sibling diff from from_tree
         let remainder_tree = from_tree
@@ -786,101 +1132,170 @@ impl Mutation for MoveHunk {
             .merge(sibling_tree.clone(), base_tree.clone())
             .await?;
 
-        // Apply hunk to destination: merge the base→sibling diff into to_tree
-        // (may be recomputed after rebase in the from_is_ancestor case)
-        let to_tree = to.tree();
-        let mut new_to_tree = to_tree
-            .merge(base_tree.clone(), sibling_tree.clone())
-            .await?;
-
         let abandon_source = remainder_tree.tree_ids() == base_tree.tree_ids();
         let description = combine_messages(&from, &to, abandon_source);
+        move_diff(
+            &mut tx,
+            &from,
+            to.clone(),
+            &base_tree,
+            &sibling_tree,
+            remainder_tree,
+            description,
+        )
+        .await?;
 
-        // Check ancestry to determine rebase strategy. The hunk must be applied to the destination's
-        // tree AFTER any ancestry-related rebasing, so we do it early if moving from an ancestor.
-        let from_is_ancestor = tx.repo().index().is_ancestor(from.id(), to.id())?;
-        let to_is_ancestor = tx.repo().index().is_ancestor(to.id(), from.id())?;
+        match ws.finish_transaction(
+            tx,
+            format!(
+                "move hunk in {} from {} to {}",
+                self.path.repo_path,
+                from.id().hex(),
+                to.id().hex()
+            ),
+        )? {
+            Some(new_status) => Ok(MutationResult::Updated { new_status }),
+            None => Ok(MutationResult::Unchanged),
+        }
+    }
+}
 
-        if to_is_ancestor {
-            // Child→Parent: apply hunk to ancestor, then handle source
-            tx.repo_mut()
-                .rewrite_commit(&to)
-                .set_tree(new_to_tree)
-                .set_description(description)
-                .write()?;
+#[async_trait::async_trait(?Send)]
+impl Mutation for SplitRevision {
+    async fn execute(self: Box<Self>, ws: &mut WorkspaceSession) -> Result<MutationResult> {
+        let target = ws.resolve_single_change(&self.id)?;
 
-            if abandon_source {
-                tx.repo_mut().record_abandoned_commit(&from);
-            } else {
+        if ws.check_immutable(vec![target.id().clone()])? {
+            precondition!("Revision is immutable");
+        }
+        if self.paths.is_empty() && self.hunks.is_empty() {
+            precondition!("No changes selected");
+        }
+
+        let mut tx = ws.start_transaction().await?;
+
+        let parents: Vec<_> = target.parents().try_collect()?;
+        if parents.len() != 1 {
+            precondition!("Cannot split a merge commit");
+        }
+        let base_tree = parents[0].tree();
+        let target_tree = target.tree();
+
+        // Construct the "sibling tree", as in MoveHunk: base_tree with just the selected changes applied.
+        // Whole files are copied from the target.
+        let store = tx.repo().store().clone();
+        let mut builder = MergedTreeBuilder::new(base_tree.clone());
+        for path in &self.paths {
+            let repo_path = RepoPath::from_internal_string(&path.repo_path)?;
+            builder.set_or_remove(repo_path.to_owned(), target_tree.path_value(repo_path)?);
+        }
+        let hunks_by_path = self
+            .hunks
+            .iter()
+            .filter(|file_hunk| {
+                !self
+                    .paths
+                    .iter()
+                    .any(|path| path.repo_path == file_hunk.path.repo_path)
+            })
+            .into_group_map_by(|file_hunk| file_hunk.path.repo_path.as_str());
+        for (path, file_hunks) in hunks_by_path {
+            let repo_path = RepoPath::from_internal_string(path)?;
+            let hunks = file_hunks.iter().map(|file_hunk| &file_hunk.hunk).collect();
+            builder.set_or_remove(
+                repo_path.to_owned(),
+                sibling_file_value(&store, &base_tree, &target_tree, repo_path, hunks).await?,
+            );
+        }
+        let sibling_tree = builder.write_tree()?;
+        if sibling_tree.tree_ids() == base_tree.tree_ids() {
+            precondition!("No changes selected");
+        }
+
+        // Remove the selected changes from the target: backout the base→sibling diff
+        let remainder_tree = target_tree
+            .clone()
+            .merge(sibling_tree.clone(), base_tree.clone())
+            .await?;
+
+        // the original keeps its change id and description, with its descendants' trees unaffected.
+        // After places the new change on top with the original's whole tree, so like jj split it
+        // takes over the original's bookmarks and working copy, leaving them at the same files.
+        let new_commit = match self.placement {
+            SplitPlacement::Before => {
+                let new_commit = tx
+                    .repo_mut()
+                    .new_commit(vec![parents[0].id().clone()], sibling_tree)
+                    .set_author(target.author().clone())
+                    .write()?;
                 tx.repo_mut()
-                    .rewrite_commit(&from)
-                    .set_tree(remainder_tree)
+                    .rewrite_commit(&target)
+                    .set_parents(vec![new_commit.id().clone()])
                     .write()?;
+                new_commit
             }
-
-            // Rebase all descendants, which includes rebasing source's descendants onto modified ancestor
-            tx.repo_mut().rebase_descendants()?;
-        } else {
-            // Parent→Child or Unrelated: modify source first
-            if abandon_source {
-                tx.repo_mut().record_abandoned_commit(&from);
-            } else {
-                tx.repo_mut()
-                    .rewrite_commit(&from)
+            SplitPlacement::After => {
+                let remainder = tx
+                    .repo_mut()
+                    .rewrite_commit(&target)
                     .set_tree(remainder_tree)
                     .write()?;
+                let new_commit = tx
+                    .repo_mut()
+                    .new_commit(vec![remainder.id().clone()], target_tree)
+                    .set_author(target.author().clone())
+                    .write()?;
+                tx.repo_mut()
+                    .set_rewritten_commit(target.id().clone(), new_commit.id().clone());
+                new_commit
             }
+            SplitPlacement::Sibling => {
+                let remainder = tx
+                    .repo_mut()
+                    .rewrite_commit(&target)
+                    .set_tree(remainder_tree)
+                    .write()?;
+                let new_commit = tx
+                    .repo_mut()
+                    .new_commit(vec![parents[0].id().clone()], sibling_tree)
+                    .set_author(target.author().clone())
+                    .write()?;
 
-            if from_is_ancestor {
-                // Parent→Child: rebase descendants first, then apply hunk to the rebased destination
-                let mut rebase_map = std::collections::HashMap::new();
-                tx.repo_mut().rebase_descendants_with_options(
-                    &RebaseOptions::default(),
-                    |old_commit, rebased_commit| {
-                        rebase_map.insert(
-                            old_commit.id().clone(),
-                            match rebased_commit {
-                                RebasedCommit::Rewritten(new_commit) => new_commit.id().clone(),
-                                RebasedCommit::Abandoned { parent_id } => parent_id,
-                            },
-                        );
-                    },
-                )?;
-
-                // The destination was rebased onto the modified source, so its tree changed.
-                // Recompute the hunk application against the rebased tree.
-                let rebased_to_id = rebase_map
-                    .get(to.id())
-                    .ok_or_else(|| anyhow!("descendant to_commit not found in rebase map"))?
-                    .clone();
-                to = tx.repo().store().get_commit(&rebased_to_id)?;
-                new_to_tree = to
-                    .tree()
-                    .merge(base_tree.clone(), sibling_tree.clone())
-                    .await?;
-            }
-
-            // Apply hunk to destination
-            tx.repo_mut()
-                .rewrite_commit(&to)
-                .set_tree(new_to_tree)
-                .set_description(description)
-                .write()?;
+                // the two halves merge back to the original tree, so children only need new parents
+                let children: Vec<_> = RevsetExpression::commit(target.id().clone())
+                    .children()
+                    .evaluate(tx.base_repo().as_ref())?
+                    .iter()
+                    .commits(&store)
+                    .try_collect()?;
+                for child in children {
+                    let child_parents = child
+                        .parent_ids()
+                        .iter()
+                        .flat_map(|parent_id| {
+                            if parent_id == target.id() {
+                                vec![remainder.id().clone(), new_commit.id().clone()]
+                            } else {
+                                vec![parent_id.clone()]
+                            }
+                        })
+                        .collect();
+                    tx.repo_mut()
+                        .rewrite_commit(&child)
+                        .set_parents(child_parents)
+                        .write()?;
+                }
+                new_commit
+            }
+        };
 
-            // Rebase all descendants as usual
-            tx.repo_mut().rebase_descendants()?;
-        }
+        tx.repo_mut().rebase_descendants()?;
 
-        match ws.finish_transaction(
-            tx,
-            format!(
-                "move hunk in {} from {} to {}",
-                self.path.repo_path,
-                from.id().hex(),
-                to.id().hex()
-            ),
-        )? {
-            Some(new_status) => Ok(MutationResult::Updated { new_status }),
+        match ws.finish_transaction(tx, format!("split commit {}", target.id().hex()))? {
+            Some(new_status) => Ok(MutationResult::UpdatedSelection {
+                new_status,
+                new_selection: ws.format_header(&new_commit, Some(false))?,
+            }),
             None => Ok(MutationResult::Unchanged),
         }
     }
@@ -1043,41 +1458,155 @@ impl Mutation for CopyHunk {
 #[async_trait::async_trait(?Send)]
 impl Mutation for GitPush {
     async fn execute(self: Box<Self>, ws: &mut WorkspaceSession) -> Result<MutationResult> {
-        let result = match self.as_ref() {
-            GitPush::AllBookmarks { remote_name } => {
-                run_jj(["git", "push", "--remote", remote_name])
-                    .current_dir(ws.workspace.workspace_root())
-                    .output()
-            }
-            GitPush::AllRemotes { branch_ref } => {
-                run_jj(["git", "push", "--bookmark", branch_ref.as_branch()?])
-                    .current_dir(ws.workspace.workspace_root())
-                    .output()
-            }
-            GitPush::RemoteBookmark {
-                remote_name,
-                branch_ref,
-            } => run_jj(["git", "push"])
-                .args(["--bookmark", branch_ref.as_branch()?])
-                .args(["--remote", remote_name])
-                .current_dir(ws.workspace.workspace_root())
-                .output(),
+        let git_repo = match ws.git_repo() {
+            Some(git_repo) => git_repo,
+            None => precondition!("No git backend"),
         };
 
-        match result {
-            Ok(output) => {
-                if output.status.success() {
-                    ws.load_at_head()?;
-                    Ok(MutationResult::Updated {
-                        new_status: ws.format_status(),
-                    })
-                } else {
-                    Ok(MutationResult::PreconditionError {
-                        message: String::from_utf8_lossy(&output.stderr).trim().into(),
-                    })
+        let mut tx = ws.start_transaction().await?;
+
+        if let GitPush::Revision { id, .. } = self.as_ref() {
+            let commit = ws.resolve_single_change(id)?;
+            set_push_bookmark(tx.repo_mut(), &ws.data.workspace_settings, &commit);
+        }
+
+        let remote_branch_updates = match plan_push(ws, tx.repo(), &git_repo, &self)? {
+            Ok(remote_branch_updates) => remote_branch_updates,
+            Err(message) => return Ok(MutationResult::PreconditionError { message }),
+        };
+        if remote_branch_updates.is_empty() {
+            precondition!("No bookmarks to push");
+        }
+
+        // push to each remote, keeping the updates of those which succeed if another fails
+        let mut pushed_remotes = Vec::new();
+        let mut failures = Vec::new();
+        let remote_count = remote_branch_updates.len();
+        for (index, (remote_name, branch_updates)) in remote_branch_updates.into_iter().enumerate()
+        {
+            // once a remote has accepted a push, its tracking bookmarks must be recorded
+            if ws.session.cancellation.is_cancelled() {
+                if pushed_remotes.is_empty() {
+                    return Err(anyhow!(Cancelled));
+                }
+                failures.push(format!("Skipped {remote_name}: cancelled"));
+                continue;
+            }
+            let cancellable = index + 1 < remote_count;
+            match push_to_remote(ws, &mut tx, &remote_name, branch_updates, cancellable) {
+                Err(err) => failures.push(format!("Failed to push to {remote_name}: {err:#}")),
+                Ok(rejected) => {
+                    if !rejected.is_empty() {
+                        failures.push(format!(
+                            "Failed to push to {remote_name}: rejected: {}",
+                            rejected.iter().map(|(name, _)| name.as_str()).join(", ")
+                        ));
+                    }
+                    pushed_remotes.push(remote_name);
                 }
             }
-            Err(e) => Err(anyhow!("Failed to execute jj git push: {e}")),
+        }
+        if pushed_remotes.is_empty() {
+            precondition!("{}", failures.join("\n"));
+        }
+
+        // the remotes have accepted the push, so their tracking bookmarks are recorded even if
+        // cancellation was requested meanwhile
+        let description = format!("push to {}", pushed_remotes.join(", "));
+        match ws.commit_transaction(tx, description)? {
+            Some(new_status) if failures.is_empty() => Ok(MutationResult::Updated { new_status }),
+            Some(new_status) => Ok(MutationResult::UpdatedWithErrors {
+                new_status,
+                message: failures.join("\n"),
+            }),
+            None if failures.is_empty() => Ok(MutationResult::Unchanged),
+            None => precondition!("{}", failures.join("\n")),
+        }
+    }
+}
+
+#[async_trait::async_trait(?Send)]
+impl Mutation for GitPushStack {
+    async fn execute(self: Box<Self>, ws: &mut WorkspaceSession) -> Result<MutationResult> {
+        if ws.git_repo().is_none() {
+            precondition!("No git backend");
+        }
+
+        let mut tx = ws.start_transaction().await?;
+
+        let head = ws.resolve_single_change(&self.head_id)?;
+        let mut stack: Vec<Commit> = ws
+            .evaluate_revset_str(&format!("trunk()..{} & mutable()", head.id().hex()))?
+            .iter()
+            .commits(ws.repo().store())
+            .try_collect()?;
+        stack.reverse(); // from trunk() up to the head
+        if stack.is_empty() {
+            precondition!("No mutable revisions between trunk() and the head");
+        }
+
+        // point each revision's bookmark at it, then work out what the remote needs
+        let settings = &ws.data.workspace_settings;
+        let mut planned = Vec::new();
+        let mut branch_updates = Vec::new();
+        for commit in &stack {
+            set_push_bookmark(tx.repo_mut(), settings, commit);
+            let branch_name = push_bookmark_name(settings, commit.change_id());
+            let update =
+                match classify_single_push(tx.repo().view(), &self.remote_name, &branch_name) {
+                    Ok(update) => update,
+                    Err(message) => return Ok(MutationResult::PreconditionError { message }),
+                };
+            let action = update
+                .as_ref()
+                .map(|update| classify_push_action(tx.repo(), update))
+                .transpose()?;
+            branch_updates.extend(update.map(|update| (branch_name.clone(), update)));
+            planned.push((commit, branch_name, action));
+        }
+        if let Err(message) =
+            check_commits_ready_to_push(ws, tx.repo(), &self.remote_name, &branch_updates)?
+        {
+            precondition!("{message}");
+        }
+
+        ws.check_cancelled()?;
+        let rejected = if branch_updates.is_empty() {
+            Vec::new()
+        } else {
+            match push_to_remote(ws, &mut tx, &self.remote_name, branch_updates, false) {
+                Ok(rejected) => rejected,
+                Err(err) => precondition!("Failed to push to {}: {err:#}", self.remote_name),
+            }
+        };
+
+        let mut pushes = Vec::new();
+        for (commit, branch_name, action) in planned {
+            let git_ref = format!("refs/heads/{}", branch_name.as_str());
+            let outcome = match (action, rejected.iter().find(|(name, _)| *name == git_ref)) {
+                (_, Some((_, reason))) => PushOutcome::Rejected {
+                    reason: reason.clone().unwrap_or_else(|| "rejected".to_owned()),
+                },
+                (Some(action), None) => PushOutcome::Pushed { action },
+                (None, None) => PushOutcome::UpToDate,
+            };
+            pushes.push(RevisionPush {
+                revision: ws.format_header(commit, None)?,
+                branch_name: branch_name.as_str().to_owned(),
+                outcome,
+            });
+        }
+
+        // the remote has accepted the push, so its tracking bookmarks are recorded even if
+        // cancellation was requested meanwhile
+        let description = format!(
+            "push stack of {} revisions to {}",
+            stack.len(),
+            self.remote_name
+        );
+        match ws.commit_transaction(tx, description)? {
+            Some(new_status) => Ok(MutationResult::UpdatedPushes { new_status, pushes }),
+            None => Ok(MutationResult::Unchanged),
         }
     }
 }
@@ -1085,70 +1614,516 @@ impl Mutation for GitPush {
 #[async_trait::async_trait(?Send)]
 impl Mutation for GitFetch {
     async fn execute(self: Box<Self>, ws: &mut WorkspaceSession) -> Result<MutationResult> {
-        let result = match self.as_ref() {
-            GitFetch::AllBookmarks { remote_name } => run_jj(["git", "fetch"])
-                .args(["--remote", remote_name])
-                .current_dir(ws.workspace.workspace_root())
-                .output(),
-            GitFetch::AllRemotes { branch_ref } => run_jj(["git", "fetch"])
-                .args(["--branch", branch_ref.as_branch()?])
-                .current_dir(ws.workspace.workspace_root())
-                .output(),
+        let git_repo = match ws.git_repo() {
+            Some(git_repo) => git_repo,
+            None => precondition!("No git backend"),
+        };
+
+        let mut tx = ws.start_transaction().await?;
+
+        let remote_patterns: Vec<(String, StringPattern)> = match *self {
+            GitFetch::AllBookmarks { remote_name } => vec![(remote_name, StringPattern::all())],
+            GitFetch::AllRemotes { branch_ref } => {
+                let branch_name = branch_ref.as_branch()?;
+                get_git_remote_names(&git_repo)
+                    .into_iter()
+                    .map(|remote_name| (remote_name, StringPattern::exact(branch_name)))
+                    .collect()
+            }
             GitFetch::RemoteBookmark {
                 remote_name,
                 branch_ref,
-            } => run_jj(["git", "fetch"])
-                .args(["--branch", branch_ref.as_branch()?])
-                .args(["--remote", remote_name])
-                .current_dir(ws.workspace.workspace_root())
-                .output(),
+            } => vec![(remote_name, StringPattern::exact(branch_ref.as_branch()?))],
         };
 
-        match result {
-            Ok(output) => {
-                if output.status.success() {
-                    ws.load_at_head()?;
-                    Ok(MutationResult::Updated {
-                        new_status: ws.format_status(),
-                    })
-                } else {
-                    Ok(MutationResult::PreconditionError {
-                        message: String::from_utf8_lossy(&output.stderr).trim().into(),
-                    })
-                }
+        let git_settings = ws.data.workspace_settings.git_settings()?;
+        let import_options = git_import_options(&ws.data.workspace_settings)?;
+        for (index, (remote_name, pattern)) in remote_patterns.iter().enumerate() {
+            ws.check_cancelled()?;
+            let operation = format!("Fetching from {remote_name}");
+            let cancel_id =
+                (index + 1 < remote_patterns.len()).then(|| ws.session.cancellation.current_id());
+            let result =
+                ws.session
+                    .callbacks
+                    .with_git(tx.repo_mut(), &operation, cancel_id, &|repo, cb| {
+                        let remote_name = RemoteName::new(remote_name);
+                        let refspecs =
+                            git::expand_fetch_refspecs(remote_name, vec![pattern.clone()])?;
+                        let mut fetcher = git::GitFetch::new(
+                            repo,
+                            git_settings.to_subprocess_options(),
+                            &import_options,
+                        )?;
+                        fetcher.fetch(remote_name, refspecs, cb, None, None)?;
+                        fetcher.import_refs()?;
+                        Ok(())
+                    });
+            if let Err(err) = result {
+                precondition!("Failed to fetch from {remote_name}: {err:#}");
             }
-            Err(e) => Err(anyhow!("Failed to execute jj git fetch: {e}")),
         }
+
+        // the fetched refs are already in the git repo, so their import is recorded even if
+        // cancellation was requested during the last fetch
+        match ws.commit_transaction(
+            tx,
+            format!(
+                "fetch from {}",
+                remote_patterns
+                    .iter()
+                    .map(|(remote_name, _)| remote_name.as_str())
+                    .unique()
+                    .join(", ")
+            ),
+        )? {
+            Some(new_status) => Ok(MutationResult::Updated { new_status }),
+            None => Ok(MutationResult::Unchanged),
+        }
+    }
+}
+
+#[async_trait::async_trait(?Send)]
+impl Mutation for AddRemote {
+    async fn execute(self: Box<Self>, ws: &mut WorkspaceSession) -> Result<MutationResult> {
+        if ws.git_repo().is_none() {
+            precondition!("No git backend");
+        }
+        if self.remote_name.is_empty() {
+            precondition!("Remote name cannot be empty");
+        }
+        if self.url.is_empty() {
+            precondition!("Remote URL cannot be empty");
+        }
+
+        let mut tx = ws.start_transaction().await?;
+
+        if let Err(err) = git::add_remote(
+            tx.repo_mut(),
+            RemoteName::new(&self.remote_name),
+            &self.url,
+            self.push_url
+                .as_deref()
+                .filter(|push_url| !push_url.is_empty()),
+            gix::remote::fetch::Tags::Included,
+            &StringExpression::all(),
+        ) {
+            precondition!("{err}");
+        }
+
+        match ws.finish_transaction(tx, format!("add git remote {}", self.remote_name))? {
+            Some(new_status) => Ok(MutationResult::Updated { new_status }),
+            None => Ok(MutationResult::Unchanged),
+        }
+    }
+}
+
+#[async_trait::async_trait(?Send)]
+impl Mutation for RemoveRemote {
+    async fn execute(self: Box<Self>, ws: &mut WorkspaceSession) -> Result<MutationResult> {
+        if ws.git_repo().is_none() {
+            precondition!("No git backend");
+        }
+
+        let mut tx = ws.start_transaction().await?;
+
+        // also forgets the remote's bookmarks, leaving local bookmarks alone
+        if let Err(err) = git::remove_remote(tx.repo_mut(), RemoteName::new(&self.remote_name)) {
+            precondition!("{err}");
+        }
+
+        match ws.finish_transaction(tx, format!("remove git remote {}", self.remote_name))? {
+            Some(new_status) => Ok(MutationResult::Updated { new_status }),
+            None => Ok(MutationResult::Unchanged),
+        }
+    }
+}
+
+#[async_trait::async_trait(?Send)]
+impl Mutation for RenameRemote {
+    async fn execute(self: Box<Self>, ws: &mut WorkspaceSession) -> Result<MutationResult> {
+        if ws.git_repo().is_none() {
+            precondition!("No git backend");
+        }
+        if self.new_name.is_empty() {
+            precondition!("Remote name cannot be empty");
+        }
+        if self.new_name == self.old_name {
+            return Ok(MutationResult::Unchanged);
+        }
+
+        let mut tx = ws.start_transaction().await?;
+
+        // also renames the remote's bookmarks, keeping their tracking state
+        if let Err(err) = git::rename_remote(
+            tx.repo_mut(),
+            RemoteName::new(&self.old_name),
+            RemoteName::new(&self.new_name),
+        ) {
+            precondition!("{err}");
+        }
+
+        match ws.finish_transaction(
+            tx,
+            format!("rename git remote {} to {}", self.old_name, self.new_name),
+        )? {
+            Some(new_status) => Ok(MutationResult::Updated { new_status }),
+            None => Ok(MutationResult::Unchanged),
+        }
+    }
+}
+
+#[async_trait::async_trait(?Send)]
+impl Mutation for SetRemoteUrl {
+    async fn execute(self: Box<Self>, ws: &mut WorkspaceSession) -> Result<MutationResult> {
+        if ws.git_repo().is_none() {
+            precondition!("No git backend");
+        }
+        if self.url.is_empty() {
+            precondition!("Remote URL cannot be empty");
+        }
+
+        // only the git config changes, so there's no operation to record
+        if let Err(err) = git::set_remote_urls(
+            ws.repo().store(),
+            RemoteName::new(&self.remote_name),
+            Some(&self.url),
+            self.push_url
+                .as_deref()
+                .filter(|push_url| !push_url.is_empty()),
+        ) {
+            precondition!("{err}");
+        }
+
+        Ok(MutationResult::Unchanged)
     }
 }
 
-// this is another case where it would be nice if we could reuse jj-cli's error messages
+// like `jj undo`, repeating an undo goes further back rather than undoing the undo
 #[async_trait::async_trait(?Send)]
 impl Mutation for UndoOperation {
     async fn execute(self: Box<Self>, ws: &mut WorkspaceSession) -> Result<MutationResult> {
-        let result = run_jj(["undo"])
-            .current_dir(ws.workspace.workspace_root())
-            .output();
+        let tx = ws.start_transaction().await?;
+
+        let loader = tx.base_repo().loader().clone();
+        let undone_op = tx.base_repo().operation().clone();
+        let op_to_undo = match restored_operation(&loader, &undone_op, UNDO_DESCRIPTION_PREFIX)? {
+            Some(restored_op) => restored_op,
+            None => undone_op.clone(),
+        };
+        let parent_op = match op_to_undo
+            .parents()
+            .collect::<Result<Vec<_>, _>>()?
+            .as_slice()
+        {
+            [parent_op] => parent_op.clone(),
+            [] => precondition!("Cannot undo repo initialization"),
+            _ => precondition!("Cannot undo a merge operation"),
+        };
+        let target_op = match restored_operation(&loader, &parent_op, UNDO_DESCRIPTION_PREFIX)? {
+            Some(original_op) => original_op,
+            None => parent_op,
+        };
 
-        match result {
-            Ok(output) => {
-                if output.status.success() {
-                    ws.load_at_head()?;
-                    let working_copy = ws.get_commit(ws.wc_id())?;
-                    let new_selection = ws.format_header(&working_copy, None)?;
+        let result = restore_operation(
+            ws,
+            tx,
+            &target_op,
+            format!("{UNDO_DESCRIPTION_PREFIX}{}", target_op.id().hex()),
+        )?;
+        if let MutationResult::UpdatedSelection { .. } = result {
+            ws.push_redo(undone_op.id().clone());
+        }
 
-                    Ok(MutationResult::UpdatedSelection {
-                        new_status: ws.format_status(),
-                        new_selection,
-                    })
-                } else {
-                    Ok(MutationResult::PreconditionError {
-                        message: String::from_utf8_lossy(&output.stderr).into(),
-                    })
-                }
-            }
-            Err(e) => Err(anyhow!("Failed to execute jj undo: {e}")),
+        Ok(result)
+    }
+}
+
+#[async_trait::async_trait(?Send)]
+impl Mutation for RedoOperation {
+    async fn execute(self: Box<Self>, ws: &mut WorkspaceSession) -> Result<MutationResult> {
+        let tx = ws.start_transaction().await?;
+
+        let target_id = match ws.pop_redo() {
+            Some(id) => id,
+            None => precondition!("Nothing to redo"),
+        };
+        let loader = tx.base_repo().loader().clone();
+        let redone_op = loader.load_operation(&target_id)?;
+
+        // like `jj redo`, restore a redo's original rather than chaining redos of redos
+        let target_op = match restored_operation(&loader, &redone_op, REDO_DESCRIPTION_PREFIX)? {
+            Some(original_op) => original_op,
+            None => redone_op,
+        };
+
+        let result = restore_operation(
+            ws,
+            tx,
+            &target_op,
+            format!("{REDO_DESCRIPTION_PREFIX}{}", target_op.id().hex()),
+        )?;
+        ws.keep_redo();
+
+        Ok(result)
+    }
+}
+
+#[async_trait::async_trait(?Send)]
+impl Mutation for RestoreOperation {
+    async fn execute(self: Box<Self>, ws: &mut WorkspaceSession) -> Result<MutationResult> {
+        let tx = ws.start_transaction().await?;
+
+        let target_op = match op_walk::resolve_op_with_repo(tx.base_repo(), &self.op_id.hex) {
+            Ok(op) => op,
+            Err(err) => precondition!("{err}"),
+        };
+
+        restore_operation(
+            ws,
+            tx,
+            &target_op,
+            format!("restore to operation {}", self.op_id.hex),
+        )
+    }
+}
+
+#[async_trait::async_trait(?Send)]
+impl Mutation for RevertOperation {
+    async fn execute(self: Box<Self>, ws: &mut WorkspaceSession) -> Result<MutationResult> {
+        let mut tx = ws.start_transaction().await?;
+
+        let bad_op = match op_walk::resolve_op_with_repo(tx.base_repo(), &self.op_id.hex) {
+            Ok(op) => op,
+            Err(err) => precondition!("{err}"),
+        };
+        let parent_op = match bad_op.parents().collect::<Result<Vec<_>, _>>()?.as_slice() {
+            [parent_op] => parent_op.clone(),
+            [] => precondition!("Cannot revert repo initialization"),
+            _ => precondition!("Cannot revert a merge operation"),
+        };
+
+        // same as resolving concurrent operations: merge in the parent using the bad op as a base
+        let loader = tx.base_repo().loader().clone();
+        let bad_repo = loader.load_at(&bad_op)?;
+        let parent_repo = loader.load_at(&parent_op)?;
+        tx.repo_mut().merge(&bad_repo, &parent_repo)?;
+
+        if tx.repo().view().get_wc_commit_id(ws.name()).is_none() {
+            precondition!(
+                "Reverting operation {} would remove workspace {}",
+                self.op_id.prefix,
+                ws.name().as_symbol()
+            );
         }
+
+        finish_operation_change(ws, tx, format!("revert operation {}", bad_op.id().hex()))
+    }
+}
+
+fn restore_operation(
+    ws: &mut WorkspaceSession,
+    mut tx: Transaction,
+    target_op: &Operation,
+    description: String,
+) -> Result<MutationResult> {
+    let target_view = target_op.view()?;
+    if target_view.get_wc_commit_id(ws.name()).is_none() {
+        precondition!(
+            "Workspace {} did not exist at operation {}",
+            ws.name().as_symbol(),
+            target_op.id().hex()
+        );
+    }
+
+    // like `jj op restore`, the last known state of the git repo isn't rolled back
+    let current_view = tx.base_repo().view().store_view();
+    tx.repo_mut().set_view(View {
+        git_refs: current_view.git_refs.clone(),
+        git_head: current_view.git_head.clone(),
+        ..target_view.store_view().clone()
+    });
+
+    finish_operation_change(ws, tx, description)
+}
+
+/// selects the working copy, since an operation-level change may have moved it anywhere
+fn finish_operation_change(
+    ws: &mut WorkspaceSession,
+    tx: Transaction,
+    description: String,
+) -> Result<MutationResult> {
+    match ws.finish_transaction(tx, description)? {
+        Some(new_status) => {
+            let working_copy = ws.get_commit(ws.wc_id())?;
+            let new_selection = ws.format_header(&working_copy, None)?;
+            Ok(MutationResult::UpdatedSelection {
+                new_status,
+                new_selection,
+            })
+        }
+        None => Ok(MutationResult::Unchanged),
+    }
+}
+
+/// moves a single revision without its descendants, like `jj rebase -r`; its children are
+/// rebased onto its old parents, and any new children onto it
+fn rebase_revision(
+    tx: &mut Transaction,
+    target: &Commit,
+    new_parent_ids: Vec<CommitId>,
+    new_child_ids: Vec<CommitId>,
+) -> Result<()> {
+    rewrite::move_commits(
+        tx.repo_mut(),
+        &MoveCommitsLocation {
+            new_parent_ids,
+            new_child_ids,
+            target: MoveCommitsTarget::Commits(vec![target.id().clone()]),
+        },
+        &RebaseOptions::default(),
+    )?;
+    Ok(())
+}
+
+/// Moves the base→sibling diff from one commit to another, abandoning the source if nothing remains.
+/// Shared by MoveHunk, MoveChanges and SquashRevision.
+async fn move_diff(
+    tx: &mut Transaction,
+    from: &Commit,
+    mut to: Commit,
+    base_tree: &MergedTree,
+    sibling_tree: &MergedTree,
+    remainder_tree: MergedTree,
+    description: String,
+) -> Result<()> {
+    // Apply diff to destination: merge the base→sibling diff into to_tree
+    // (may be recomputed after rebase in the from_is_ancestor case)
+    let to_tree = to.tree();
+    let mut new_to_tree = to_tree
+        .merge(base_tree.clone(), sibling_tree.clone())
+        .await?;
+
+    let abandon_source = remainder_tree.tree_ids() == base_tree.tree_ids();
+
+    // Check ancestry to determine rebase strategy. The diff must be applied to the destination's
+    // tree AFTER any ancestry-related rebasing, so we do it early if moving from an ancestor.
+    let from_is_ancestor = tx.repo().index().is_ancestor(from.id(), to.id())?;
+    let to_is_ancestor = tx.repo().index().is_ancestor(to.id(), from.id())?;
+
+    if to_is_ancestor {
+        // Child→Parent: apply diff to ancestor, then handle source
+        tx.repo_mut()
+            .rewrite_commit(&to)
+            .set_tree(new_to_tree)
+            .set_description(description)
+            .write()?;
+
+        if abandon_source {
+            tx.repo_mut().record_abandoned_commit(from);
+        } else {
+            tx.repo_mut()
+                .rewrite_commit(from)
+                .set_tree(remainder_tree)
+                .write()?;
+        }
+
+        // Rebase all descendants, which includes rebasing source's descendants onto modified ancestor
+        tx.repo_mut().rebase_descendants()?;
+    } else {
+        // Parent→Child or Unrelated: modify source first
+        if abandon_source {
+            tx.repo_mut().record_abandoned_commit(from);
+        } else {
+            tx.repo_mut()
+                .rewrite_commit(from)
+                .set_tree(remainder_tree)
+                .write()?;
+        }
+
+        if from_is_ancestor {
+            // Parent→Child: rebase descendants first, then apply diff to the rebased destination
+            let mut rebase_map = HashMap::new();
+            tx.repo_mut().rebase_descendants_with_options(
+                &RebaseOptions::default(),
+                |old_commit, rebased_commit| {
+                    rebase_map.insert(
+                        old_commit.id().clone(),
+                        match rebased_commit {
+                            RebasedCommit::Rewritten(new_commit) => new_commit.id().clone(),
+                            RebasedCommit::Abandoned { parent_id } => parent_id,
+                        },
+                    );
+                },
+            )?;
+
+            // The destination was rebased onto the modified source, so its tree changed.
+            // Recompute the diff application against the rebased tree.
+            let rebased_to_id = rebase_map
+                .get(to.id())
+                .ok_or_else(|| anyhow!("descendant to_commit not found in rebase map"))?
+                .clone();
+            to = tx.repo().store().get_commit(&rebased_to_id)?;
+            new_to_tree = to
+                .tree()
+                .merge(base_tree.clone(), sibling_tree.clone())
+                .await?;
+        }
+
+        // Apply diff to destination
+        tx.repo_mut()
+            .rewrite_commit(&to)
+            .set_tree(new_to_tree)
+            .set_description(description)
+            .write()?;
+
+        // Rebase all descendants as usual
+        tx.repo_mut().rebase_descendants()?;
+    }
+
+    Ok(())
+}
+
+/// matches the given paths, or everything if there are none
+fn build_matcher(paths: &[TreePath]) -> Result<Box<dyn Matcher>> {
+    if paths.is_empty() {
+        Ok(Box::new(EverythingMatcher))
+    } else {
+        let repo_paths = paths
+            .iter()
+            .map(|path| RepoPath::from_internal_string(&path.repo_path))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Box::new(FilesMatcher::new(repo_paths)))
+    }
+}
+
+/// names the first of several commits in an operation description
+fn describe_commits(commits: &[Commit]) -> String {
+    match commits {
+        [commit] => format!("commit {}", commit.id().hex()),
+        [commit, rest @ ..] => format!("commit {} and {} more", commit.id().hex(), rest.len()),
+        [] => "no commits".to_owned(),
+    }
+}
+
+/// same prefixes as `jj undo` and `jj redo`, so that the CLI and gg can continue each other's
+/// undo and redo stacks
+const UNDO_DESCRIPTION_PREFIX: &str = "undo: restore to operation ";
+const REDO_DESCRIPTION_PREFIX: &str = "redo: restore to operation ";
+
+/// the operation an undo or redo restored, if `op`'s description starts with `prefix`
+fn restored_operation(
+    loader: &RepoLoader,
+    op: &Operation,
+    prefix: &str,
+) -> Result<Option<Operation>> {
+    match op.metadata().description.strip_prefix(prefix) {
+        Some(hex) => {
+            let id = OperationId::try_from_hex(hex)
+                .ok_or_else(|| anyhow!("Invalid operation id in description: {hex}"))?;
+            Ok(Some(loader.load_operation(&id)?))
+        }
+        None => Ok(None),
     }
 }
 
@@ -1275,6 +2250,36 @@ fn apply_hunk_to_base(base_content: &[u8], hunk: &crate::messages::ChangeHunk) -
     Ok(result_bytes)
 }
 
+/// The file at `path` in a virtual sibling commit of `source_tree` which contains only `hunks`.
+///
+/// The hunks are applied to the file's content in `base_tree` from the bottom up, so that each
+/// one's line numbers are still relative to the base. The executable bit comes from `source_tree`.
+async fn sibling_file_value(
+    store: &Arc<Store>,
+    base_tree: &MergedTree,
+    source_tree: &MergedTree,
+    path: &RepoPath,
+    mut hunks: Vec<&crate::messages::ChangeHunk>,
+) -> Result<Merge<Option<TreeValue>>> {
+    hunks.sort_by_key(|hunk| hunk.location.from_file.start);
+
+    let mut content = read_file_content(store, base_tree, path).await?;
+    for hunk in hunks.iter().rev() {
+        content = apply_hunk_to_base(&content, hunk)?;
+    }
+    let id = store.write_file(path, &mut content.as_slice()).await?;
+    let executable = match source_tree.path_value(path)?.into_resolved() {
+        Ok(Some(TreeValue::File { executable, .. })) => executable,
+        _ => false,
+    };
+
+    Ok(Merge::normal(TreeValue::File {
+        id,
+        executable,
+        copy_id: CopyId::placeholder(),
+    }))
+}
+
 fn update_tree_entry(
     _store: &Arc<jj_lib::store::Store>,
     original_tree: &MergedTree,
@@ -1294,3 +2299,39 @@ fn update_tree_entry(
     let new_tree = builder.write_tree()?;
     Ok(new_tree)
 }
+
+/// sends bookmark updates to a remote, returning the refs it rejected along with any reasons given;
+/// `cancellable` is true if pushes to other remotes follow
+fn push_to_remote(
+    ws: &WorkspaceSession,
+    tx: &mut Transaction,
+    remote_name: &str,
+    branch_updates: Vec<(RefNameBuf, BookmarkPushUpdate)>,
+    cancellable: bool,
+) -> Result<Vec<(String, Option<String>)>> {
+    let git_settings = ws.data.workspace_settings.git_settings()?;
+    let targets = GitBranchPushTargets { branch_updates };
+    let operation = format!("Pushing to {remote_name}");
+    let cancel_id = cancellable.then(|| ws.session.cancellation.current_id());
+    let rejected = RefCell::new(Vec::new());
+    ws.session
+        .callbacks
+        .with_git(tx.repo_mut(), &operation, cancel_id, &|repo, cb| {
+            let stats = git::push_branches(
+                repo,
+                git_settings.to_subprocess_options(),
+                RemoteName::new(remote_name),
+                &targets,
+                cb,
+            )?;
+            rejected.borrow_mut().extend(
+                stats
+                    .rejected
+                    .into_iter()
+                    .chain(stats.remote_rejected)
+                    .map(|(name, reason)| (name.as_str().to_owned(), reason)),
+            );
+            Ok(())
+        })?;
+    Ok(rejected.into_inner())
+}
@@ -1,19 +1,22 @@
 use std::{
     borrow::Borrow,
+    collections::{BTreeMap, HashMap, HashSet},
     io::Write,
     iter::{Peekable, Skip},
     mem,
     ops::Range,
+    slice,
 };
 
-use anyhow::{Result, anyhow};
+use anyhow::{Context, Result, anyhow};
 
 use futures_util::{StreamExt, try_join};
 use gix::bstr::ByteVec;
-use itertools::Itertools;
+use itertools::{EitherOrBoth, Itertools};
 use jj_cli::diff_util::LineDiffOptions;
 use jj_lib::{
-    backend::CommitId,
+    backend::{ChangeId, CommitId},
+    commit::Commit,
     conflicts::{
         self, ConflictMarkerStyle, ConflictMaterializeOptions, MaterializedFileValue,
         MaterializedTreeValue,
@@ -23,25 +26,37 @@ use jj_lib::{
         ContentDiff, DiffHunk, DiffHunkKind, find_line_ranges,
     },
     diff_presentation::LineCompareMode,
+    evolution,
     files::FileMergeHunkLevel,
     graph::{GraphEdge, GraphEdgeType, TopoGroupedGraphIterator},
     matchers::EverythingMatcher,
     merge::SameChange,
     merged_tree::{TreeDiffEntry, TreeDiffStream},
-    ref_name::{RefNameBuf, RemoteNameBuf, RemoteRefSymbol},
-    repo::Repo,
+    op_walk,
+    ref_name::{RefNameBuf, RemoteName, RemoteNameBuf, RemoteRefSymbol},
+    repo::{ReadonlyRepo, Repo},
     repo_path::RepoPath,
-    revset::{Revset, RevsetEvaluationError},
+    revset::{Revset, RevsetEvaluationError, RevsetExpression, RevsetIteratorExt},
     rewrite,
     tree_merge::MergeOptions,
 };
 
 use crate::messages::{
-    ChangeHunk, ChangeKind, FileRange, HunkLocation, LogCoordinates, LogLine, LogPage, LogRow,
-    MultilineString, RevChange, RevConflict, RevId, RevResult,
+    self, BookmarkPush, ChangeHunk, ChangeKind, EvolutionEntry, EvolutionResult, FileRange,
+    HunkLocation, LogCoordinates, LogLine, LogPage, LogRow, MultilineString, OperationDiff,
+    OperationPage, PushPlan, RefDiff, RemotePush, RevChange, RevConflict, RevHeader, RevId,
+    RevResult, RewrittenRevision, StoreRef,
 };
 
-use super::{WorkspaceSession, gui_util::get_git_remote_names};
+use super::{
+    WorkspaceSession,
+    gui_util::{
+        build_ref_index, classify_push_action, get_git_remote_names, plan_push, set_push_bookmark,
+    },
+};
+
+/// push previews list at most this many of the commits to be sent
+const MAX_PUSH_PLAN_COMMITS: usize = 100;
 
 struct LogStem {
     source: LogCoordinates,
@@ -127,6 +142,8 @@ impl<'q, 'w> QuerySession<'q, 'w> {
         let root_id = self.ws.repo().store().root_commit_id().clone();
 
         while let Some(Ok((commit_id, commit_edges))) = self.iter.next() {
+            self.ws.check_cancelled()?;
+
             // output lines to draw for the current row
             let mut lines: Vec<LogLine> = Vec::new();
 
@@ -374,6 +391,45 @@ pub async fn query_revision(ws: &WorkspaceSession<'_>, id: RevId) -> Result<RevR
     })
 }
 
+/// walks a change's predecessors, newest first, diffing each commit against the commits it replaced
+pub async fn query_evolution(ws: &WorkspaceSession<'_>, id: RevId) -> Result<EvolutionResult> {
+    let commit = match ws.resolve_optional_id(&id)? {
+        Some(commit) => commit,
+        None => return Ok(EvolutionResult::NotFound { id }),
+    };
+
+    let mut entries = Vec::new();
+    for entry in evolution::walk_predecessors(ws.repo(), slice::from_ref(commit.id())) {
+        let entry = entry?;
+
+        let mut changes = Vec::new();
+        let predecessors: Vec<_> = entry.predecessors().try_collect()?;
+        if !predecessors.is_empty() {
+            let predecessor_tree =
+                rewrite::rebase_to_dest_parent(ws.repo(), &predecessors, &entry.commit).await?;
+            let tree_diff = predecessor_tree.diff_stream(&entry.commit.tree(), &EverythingMatcher);
+            format_tree_changes(ws, &mut changes, tree_diff).await?;
+        }
+
+        entries.push(EvolutionEntry {
+            header: ws.format_header(&entry.commit, None)?,
+            operation: entry
+                .operation
+                .as_ref()
+                .map(|op| ws.format_operation(op))
+                .transpose()?,
+            predecessor_ids: entry
+                .predecessor_ids()
+                .iter()
+                .map(|id| ws.format_commit_id(id))
+                .collect(),
+            changes,
+        });
+    }
+
+    Ok(EvolutionResult::Detail { entries })
+}
+
 pub fn query_remotes(
     ws: &WorkspaceSession,
     tracking_branch: Option<String>,
@@ -405,7 +461,257 @@ pub fn query_remotes(
     Ok(matching_remotes)
 }
 
-async fn format_tree_changes(
+/// previews a push without contacting any remote, like `jj git push --dry-run`
+pub fn query_push_plan(ws: &WorkspaceSession, push: &messages::GitPush) -> Result<PushPlan> {
+    let git_repo = match ws.git_repo() {
+        Some(git_repo) => git_repo,
+        None => return Err(anyhow!("No git backend")),
+    };
+
+    // pushing a revision creates its bookmark first, so plan against a repo where it exists
+    let mut tx = ws.start_preview_transaction();
+    if let messages::GitPush::Revision { id, .. } = push {
+        let commit = ws.resolve_single_change(id)?;
+        set_push_bookmark(tx.repo_mut(), &ws.data.workspace_settings, &commit);
+    }
+
+    let remote_branch_updates = match plan_push(ws, tx.repo(), &git_repo, push)? {
+        Ok(remote_branch_updates) => remote_branch_updates,
+        Err(message) => return Ok(PushPlan::Blocked { message }),
+    };
+
+    let mut remotes = Vec::new();
+    for (remote_name, branch_updates) in remote_branch_updates {
+        let mut bookmarks = Vec::new();
+        let mut new_heads = Vec::new();
+        for (branch_name, update) in branch_updates {
+            let action = classify_push_action(ws.repo(), &update)?;
+            bookmarks.push(BookmarkPush {
+                branch_name: branch_name.as_str().to_owned(),
+                action,
+                old_target: update
+                    .old_target
+                    .map(|id| ws.format_header(&ws.get_commit(&id)?, None))
+                    .transpose()?,
+                new_target: update
+                    .new_target
+                    .as_ref()
+                    .map(|id| ws.format_header(&ws.get_commit(id)?, None))
+                    .transpose()?,
+            });
+            new_heads.extend(update.new_target);
+        }
+
+        // everything the remote doesn't already have will be sent
+        let remote_heads = ws
+            .view()
+            .remote_bookmarks(RemoteName::new(&remote_name))
+            .flat_map(|(_, remote_ref)| remote_ref.target.added_ids())
+            .cloned()
+            .collect();
+        let new_commits_expr = RevsetExpression::commits(new_heads)
+            .ancestors()
+            .minus(&RevsetExpression::commits(remote_heads).ancestors())
+            .minus(&RevsetExpression::root());
+        let mut new_commits = Vec::new();
+        let mut has_more_commits = false;
+        for commit in ws
+            .evaluate_revset_expr(new_commits_expr)?
+            .iter()
+            .commits(ws.repo().store())
+        {
+            if new_commits.len() == MAX_PUSH_PLAN_COMMITS {
+                has_more_commits = true;
+                break;
+            }
+            new_commits.push(ws.format_header(&commit?, None)?);
+        }
+
+        remotes.push(RemotePush {
+            remote_name,
+            bookmarks,
+            new_commits,
+            has_more_commits,
+        });
+    }
+
+    Ok(PushPlan::Ready { remotes })
+}
+
+/// pages through the operation log, starting from the head even if a past operation is loaded
+pub fn query_operations(
+    ws: &WorkspaceSession,
+    skip: usize,
+    max_results: usize,
+) -> Result<OperationPage> {
+    let loader = ws.workspace.repo_loader();
+    let head_ops =
+        op_walk::get_current_head_ops(loader.op_store(), loader.op_heads_store().as_ref())?;
+    let mut ops = op_walk::walk_ancestors(&head_ops).skip(skip);
+
+    let mut operations = Vec::with_capacity(max_results);
+    for op in ops.by_ref().take(max_results) {
+        operations.push(ws.format_operation(&op?)?);
+    }
+
+    Ok(OperationPage {
+        operations,
+        has_more: ops.next().is_some(),
+    })
+}
+
+/// compares the revisions and refs visible at two operations - by default, an operation and its parent
+pub fn query_operation_diff(
+    ws: &WorkspaceSession,
+    from_id: Option<&messages::OperationId>,
+    to_id: &messages::OperationId,
+) -> Result<OperationDiff> {
+    let loader = ws.workspace.repo_loader();
+    let to_op = op_walk::resolve_op_for_load(loader, &to_id.hex)?;
+    let from_op = match from_id {
+        Some(from_id) => op_walk::resolve_op_for_load(loader, &from_id.hex)?,
+        None => to_op.parents().exactly_one().map_err(|_| {
+            anyhow!(
+                "Operation {} does not have a single parent to compare with",
+                to_id.prefix
+            )
+        })??,
+    };
+
+    let from_repo = loader.load_at(&from_op).context("load operation")?;
+    let to_repo = loader.load_at(&to_op).context("load operation")?;
+
+    // commits from other operations may be unknown to the loaded one, e.g. when viewing the past
+    let format_header = |commit: &Commit| -> Result<RevHeader> {
+        let known_immutable = (!ws.repo().index().has_id(commit.id())?).then_some(false);
+        ws.format_header(commit, known_immutable)
+    };
+
+    // like `jj op diff`, evaluate against an index holding both operations' commits
+    let mut tx = to_repo.start_transaction();
+    tx.repo_mut().merge_index(&from_repo)?;
+    let merged_repo = tx.repo();
+
+    // a visible commit replaced by another with the same change id was rewritten, not abandoned
+    let added_commits = visible_difference(merged_repo, &to_repo, &from_repo)?;
+    let removed_commits = visible_difference(merged_repo, &from_repo, &to_repo)?;
+    let added_changes: HashSet<&ChangeId> = added_commits
+        .iter()
+        .map(|commit| commit.change_id())
+        .collect();
+
+    let mut old_ids: HashMap<&ChangeId, Vec<messages::CommitId>> = HashMap::new();
+    let mut abandoned = Vec::new();
+    for commit in &removed_commits {
+        if added_changes.contains(commit.change_id()) {
+            old_ids
+                .entry(commit.change_id())
+                .or_default()
+                .push(ws.format_commit_id(commit.id()));
+        } else {
+            abandoned.push(format_header(commit)?);
+        }
+    }
+
+    let mut added = Vec::new();
+    let mut rewritten = Vec::new();
+    for commit in &added_commits {
+        match old_ids.remove(commit.change_id()) {
+            Some(old_ids) => rewritten.push(RewrittenRevision {
+                header: format_header(commit)?,
+                old_ids,
+            }),
+            None => added.push(format_header(commit)?),
+        }
+    }
+
+    let format_ids = |ids: Vec<CommitId>| {
+        ids.iter()
+            .map(|id| ws.format_commit_id(id))
+            .collect::<Vec<_>>()
+    };
+
+    let mut refs = Vec::new();
+    for pair in targets_by_ref(&from_repo)
+        .into_iter()
+        .merge_join_by(targets_by_ref(&to_repo), |(a, _), (b, _)| a.cmp(b))
+    {
+        let (r#ref, old_targets, new_targets) = match pair {
+            EitherOrBoth::Both((_, (_, old_targets)), (_, (r#ref, new_targets))) => {
+                if old_targets == new_targets {
+                    continue;
+                }
+                (r#ref, old_targets, new_targets)
+            }
+            EitherOrBoth::Left((_, (r#ref, old_targets))) => (r#ref, old_targets, vec![]),
+            EitherOrBoth::Right((_, (r#ref, new_targets))) => (r#ref, vec![], new_targets),
+        };
+        refs.push(RefDiff {
+            r#ref,
+            old_targets: format_ids(old_targets),
+            new_targets: format_ids(new_targets),
+        });
+    }
+
+    Ok(OperationDiff {
+        from_op: ws.format_operation_id(from_op.id()),
+        to_op: ws.format_operation_id(to_op.id()),
+        added,
+        rewritten,
+        abandoned,
+        refs,
+    })
+}
+
+/// commits visible in `repo` which are not visible in `other`; `merged_repo` must index both
+fn visible_difference(
+    merged_repo: &dyn Repo,
+    repo: &ReadonlyRepo,
+    other: &ReadonlyRepo,
+) -> Result<Vec<Commit>> {
+    let expression = RevsetExpression::commits(repo.view().heads().iter().cloned().collect())
+        .ancestors()
+        .minus(
+            &RevsetExpression::commits(other.view().heads().iter().cloned().collect()).ancestors(),
+        );
+
+    let commits = expression
+        .evaluate(merged_repo)?
+        .iter()
+        .commits(merged_repo.store())
+        .try_collect()?;
+    Ok(commits)
+}
+
+/// refs keyed by kind, name and remote, along with their sorted targets
+fn targets_by_ref(
+    repo: &ReadonlyRepo,
+) -> BTreeMap<(u8, String, String), (StoreRef, Vec<CommitId>)> {
+    let mut targets: BTreeMap<_, (StoreRef, Vec<CommitId>)> = BTreeMap::new();
+    for (id, r#ref) in build_ref_index(repo).iter() {
+        let key = match r#ref {
+            StoreRef::LocalBookmark { branch_name, .. } => (0, branch_name.clone(), String::new()),
+            StoreRef::RemoteBookmark {
+                branch_name,
+                remote_name,
+                ..
+            } => (1, branch_name.clone(), remote_name.clone()),
+            StoreRef::Tag { tag_name } => (2, tag_name.clone(), String::new()),
+        };
+        targets
+            .entry(key)
+            .or_insert_with(|| (r#ref.clone(), vec![]))
+            .1
+            .push(id.clone());
+    }
+
+    for (_, ids) in targets.values_mut() {
+        ids.sort();
+    }
+    targets
+}
+
+pub async fn format_tree_changes(
     ws: &WorkspaceSession<'_>,
     changes: &mut Vec<RevChange>,
     mut tree_diff: TreeDiffStream<'_>,
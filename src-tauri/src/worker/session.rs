@@ -1,17 +1,15 @@
 use std::{
-    panic::AssertUnwindSafe,
     path::PathBuf,
     sync::mpsc::{Receiver, Sender},
 };
 
 use anyhow::{Context, Result, anyhow};
-use futures_util::FutureExt;
 use jj_cli::config::ConfigEnv;
 use jj_lib::config::{ConfigNamePathBuf, ConfigSource};
 
 use super::{
-    Mutation, WorkerSession,
-    gui_util::WorkspaceSession,
+    Cancelled, Mutation, ViewingPastOperation, WorkerSession,
+    gui_util::{SnapshotError, WorkspaceSession},
     queries::{self, QueryState},
 };
 
@@ -35,10 +33,19 @@ pub enum SessionEvent {
         tx: Sender<Result<messages::RepoConfig>>,
         wd: Option<PathBuf>,
     },
+    CreateWorkspace {
+        tx: Sender<Result<()>>,
+        wd: PathBuf,
+        source: messages::WorkspaceSource,
+    },
     QueryRevision {
         tx: Sender<Result<messages::RevResult>>,
         id: messages::RevId,
     },
+    QueryEvolution {
+        tx: Sender<Result<messages::EvolutionResult>>,
+        id: messages::RevId,
+    },
     QueryRemotes {
         tx: Sender<Result<Vec<String>>>,
         tracking_branch: Option<String>,
@@ -50,9 +57,27 @@ pub enum SessionEvent {
     QueryLogNextPage {
         tx: Sender<Result<messages::LogPage>>,
     },
+    QueryOperations {
+        tx: Sender<Result<messages::OperationPage>>,
+        skip: usize,
+        max_results: usize,
+    },
+    QueryOperationDiff {
+        tx: Sender<Result<messages::OperationDiff>>,
+        from_id: Option<messages::OperationId>,
+        to_id: messages::OperationId,
+    },
+    QueryPushPlan {
+        tx: Sender<Result<messages::PushPlan>>,
+        push: messages::GitPush,
+    },
     ExecuteSnapshot {
         tx: Sender<Option<messages::RepoStatus>>,
     },
+    ViewOperation {
+        tx: Sender<Result<messages::RepoConfig>>,
+        op_id: Option<messages::OperationId>,
+    },
     ExecuteMutation {
         tx: Sender<messages::MutationResult>,
         mutation: Box<dyn Mutation + Send + Sync>,
@@ -121,6 +146,9 @@ impl Session for WorkerSession {
                         tx.send(Err(anyhow!("global settings not found")))?;
                     }
                 }
+                Ok(SessionEvent::CreateWorkspace { tx, wd, source }) => {
+                    tx.send(self.create_workspace(&wd, &source))?;
+                }
                 Ok(SessionEvent::OpenWorkspace { mut tx, mut wd }) => loop {
                     let resolved_wd = match wd.clone().or(latest_wd) {
                         Some(wd) => wd,
@@ -151,7 +179,13 @@ impl Session for WorkerSession {
 
                     latest_wd = Some(resolved_wd);
 
-                    ws.import_and_snapshot(false).await?;
+                    // a workspace which can't be snapshotted can still be viewed
+                    if let Err(err) = ws.import_and_snapshot(false).await {
+                        match err.downcast_ref::<SnapshotError>() {
+                            Some(err) => log::warn!("{err}"),
+                            None => return Err(err),
+                        }
+                    }
 
                     tx.send(ws.format_config())?;
 
@@ -192,18 +226,38 @@ impl Session for WorkspaceSession<'_> {
                 evt?
             };
 
+            // cancellation requests only apply to the operation which was running at the time
+            self.session.cancellation.begin();
+
             match next_event {
                 SessionEvent::EndSession => return Ok(WorkspaceResult::SessionComplete),
                 SessionEvent::OpenWorkspace { tx, wd: cwd } => {
                     return Ok(WorkspaceResult::Reopen(tx, cwd));
                 }
+                SessionEvent::CreateWorkspace { tx, wd, source } => {
+                    tx.send(self.session.create_workspace(&wd, &source))?
+                }
                 SessionEvent::QueryRevision { tx, id } => {
                     tx.send(queries::query_revision(&self, id).await)?
                 }
+                SessionEvent::QueryEvolution { tx, id } => {
+                    tx.send(queries::query_evolution(&self, id).await)?
+                }
                 SessionEvent::QueryRemotes {
                     tx,
                     tracking_branch,
                 } => tx.send(queries::query_remotes(&self, tracking_branch))?,
+                SessionEvent::QueryOperations {
+                    tx,
+                    skip,
+                    max_results,
+                } => tx.send(queries::query_operations(&self, skip, max_results))?,
+                SessionEvent::QueryOperationDiff { tx, from_id, to_id } => tx.send(
+                    queries::query_operation_diff(&self, from_id.as_ref(), &to_id),
+                )?,
+                SessionEvent::QueryPushPlan { tx, push } => {
+                    tx.send(queries::query_push_plan(&self, &push))?
+                }
                 SessionEvent::QueryLog {
                     tx,
                     query: revset_string,
@@ -229,22 +283,49 @@ impl Session for WorkspaceSession<'_> {
                     handle_query(&mut state, &self, tx, rx, revset_string, None).await?;
                 }
                 SessionEvent::ExecuteSnapshot { tx } => {
-                    let updated_head = self.load_at_head()?; // alternatively, this could be folded into snapshot so that it's done by all mutations
-                    if self.import_and_snapshot(false).await? || updated_head {
+                    let old_error = self.snapshot_error().map(str::to_owned);
+                    let snapshotted = match self.import_and_snapshot(false).await {
+                        Ok(snapshotted) => snapshotted,
+                        Err(err) => match err.downcast_ref::<SnapshotError>() {
+                            Some(err) => {
+                                log::warn!("{err}");
+                                false
+                            }
+                            None => return Err(err),
+                        },
+                    };
+                    // the status reports snapshot errors, so it's resent when they change
+                    if snapshotted || self.snapshot_error() != old_error.as_deref() {
                         tx.send(Some(self.format_status()))?;
                     } else {
                         tx.send(None)?;
                     }
                 }
+                SessionEvent::ViewOperation { tx, op_id } => {
+                    match self.load_at_operation(op_id.as_ref()) {
+                        Ok(()) => tx.send(self.format_config())?,
+                        Err(err) => tx.send(Err(err))?,
+                    }
+                }
+                SessionEvent::ExecuteMutation { tx, .. } if self.is_readonly() => {
+                    tx.send(messages::MutationResult::PreconditionError {
+                        message: ViewingPastOperation.to_string(),
+                    })?;
+                }
                 SessionEvent::ExecuteMutation { tx, mutation } => {
                     let mut error_message = mutation.as_ref().describe();
-                    match AssertUnwindSafe(mutation.execute(&mut self))
-                        .catch_unwind()
-                        .await
-                    {
+                    match self.execute_mutation(mutation).await {
                         Ok(result) => {
                             tx.send(match result {
                                 Ok(result) => result,
+                                Err(err) if err.is::<Cancelled>() => {
+                                    messages::MutationResult::Cancelled
+                                }
+                                Err(err) if err.is::<SnapshotError>() => {
+                                    messages::MutationResult::PreconditionError {
+                                        message: err.to_string(),
+                                    }
+                                }
                                 Err(err) => {
                                     let err = err.context(error_message);
                                     log::error!("{err:?}");
@@ -329,14 +410,29 @@ impl Session for queries::QuerySession<'_, '_> {
         loop {
             let evt = rx.recv();
             log::debug!("LogQuery handling {evt:?}");
+            self.ws.session.cancellation.begin();
             match evt {
                 Ok(SessionEvent::QueryRevision { tx, id }) => {
                     tx.send(queries::query_revision(self.ws, id).await)?
                 }
+                Ok(SessionEvent::QueryEvolution { tx, id }) => {
+                    tx.send(queries::query_evolution(self.ws, id).await)?
+                }
                 Ok(SessionEvent::QueryRemotes {
                     tx,
                     tracking_branch,
                 }) => tx.send(queries::query_remotes(self.ws, tracking_branch))?,
+                Ok(SessionEvent::QueryOperations {
+                    tx,
+                    skip,
+                    max_results,
+                }) => tx.send(queries::query_operations(self.ws, skip, max_results))?,
+                Ok(SessionEvent::QueryOperationDiff { tx, from_id, to_id }) => tx.send(
+                    queries::query_operation_diff(self.ws, from_id.as_ref(), &to_id),
+                )?,
+                Ok(SessionEvent::QueryPushPlan { tx, push }) => {
+                    tx.send(queries::query_push_plan(self.ws, &push))?
+                }
                 Ok(SessionEvent::QueryLogNextPage { tx }) => tx.send(self.get_page())?,
                 Ok(unhandled) => return Ok(QueryResult(unhandled, self.state)),
                 Err(err) => return Err(anyhow!(err)),
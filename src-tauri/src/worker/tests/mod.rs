@@ -1,6 +1,6 @@
 use crate::{
     messages::{ChangeId, CommitId, RevId},
-    worker::{WorkerSession, WorkspaceSession},
+    worker::{WorkerSession, WorkspaceSession, queries},
 };
 use anyhow::Result;
 use jj_lib::{backend::TreeValue, commit::Commit, repo_path::RepoPath};
@@ -223,3 +223,34 @@ async fn transaction_snapshot_path_is_visible() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn historical_operation_is_readonly() -> Result<()> {
+    let repo = mkrepo();
+
+    let mut session = WorkerSession::default();
+    let mut ws = session.load_directory(repo.path())?;
+    let old_wc = ws.wc_id().clone();
+    let old_op = queries::query_operations(&ws, 0, 1)?
+        .operations
+        .remove(0)
+        .id;
+
+    fs::write(repo.path().join("new.txt"), []).unwrap();
+    ws.import_and_snapshot(true).await?;
+    let new_wc = ws.wc_id().clone();
+
+    ws.load_at_operation(Some(&old_op))?;
+    assert!(ws.is_readonly());
+    assert_eq!(&old_wc, ws.wc_id());
+    assert!(ws.format_status().historical_operation.is_some());
+    assert!(ws.start_transaction().await.is_err());
+    assert!(!ws.import_and_snapshot(true).await?);
+
+    ws.load_at_operation(None)?;
+    assert!(!ws.is_readonly());
+    assert_eq!(&new_wc, ws.wc_id());
+    assert!(ws.format_status().historical_operation.is_none());
+
+    Ok(())
+}
@@ -1,15 +1,24 @@
 use super::{get_rev, mkrepo, revs};
 use crate::{
     messages::{
-        AbandonRevisions, ChangeHunk, CheckoutRevision, CopyChanges, CopyHunk, CreateRevision,
-        DescribeRevision, DuplicateRevisions, FileRange, HunkLocation, InsertRevision, MoveChanges,
-        MoveHunk, MoveSource, MultilineString, MutationResult, RevResult, TreePath,
+        AbandonRevisions, AbsorbChanges, AddRemote, BackoutRevisions, ChangeHunk, CheckoutRevision,
+        CopyChanges, CopyHunk, CreateRef, CreateRevision, DeleteRef, DescribeRevision,
+        DuplicateRevisions, FileHunk, FileRange, GitFetch, GitPush, GitPushStack, HunkLocation,
+        InsertRevision, MoveChanges, MoveHunk, MoveRef, MoveRevision, MoveSource, MultilineString,
+        MutationResult, PushAction, PushOutcome, RedoOperation, RemoveRemote, RenameBranch,
+        RenameRemote, ReorderRevisions, RepoConfig, RestoreHiddenRevision, RestoreOperation, RevId,
+        RevResult, RevertOperation, SetRemoteUrl, SplitPlacement, SplitRevision, SquashMessage,
+        SquashRevision, StoreRef, TreePath, UndoOperation,
     },
-    worker::{Mutation, WorkerSession, queries},
+    worker::{Cancelled, Mutation, WorkerSession, WorkspaceSession, queries},
 };
 use anyhow::Result;
 use assert_matches::assert_matches;
+use jj_lib::object_id::ObjectId;
+use jj_lib::ref_name::{RefName, RemoteName};
 use std::fs;
+use std::process::Command;
+use tempfile::{TempDir, tempdir};
 use tokio::io::AsyncReadExt;
 
 #[tokio::test]
@@ -34,6 +43,30 @@ async fn abandon_revisions() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn backout_revisions() -> Result<()> {
+    let repo = mkrepo();
+
+    let mut session = WorkerSession::default();
+    let mut ws = session.load_directory(repo.path())?;
+    let old_wc = ws.wc_id().clone();
+
+    let result = BackoutRevisions {
+        ids: vec![revs::main_bookmark()],
+    }
+    .execute_unboxed(&mut ws)
+    .await?;
+    assert_matches!(result, MutationResult::Updated { .. });
+
+    // the reversal is a new child of the working copy, which stays where it was
+    assert_eq!(&old_wc, ws.wc_id());
+    let page = queries::query_log(&ws, "children(@)", 2)?;
+    assert_eq!(1, page.rows.len());
+    assert!(page.rows[0].revision.description.lines[0].starts_with("Revert \"rename c.txt"));
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn checkout_revision() -> Result<()> {
     let repo = mkrepo();
@@ -210,6 +243,51 @@ async fn describe_revision_with_snapshot() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn describe_revision_with_oversized_file() -> Result<()> {
+    let repo = mkrepo();
+
+    let mut session = WorkerSession::default();
+    let mut ws = session.load_directory(repo.path())?;
+
+    // larger than jj's default snapshot.max-new-file-size of 1MiB
+    fs::write(repo.path().join("large.bin"), vec![0u8; 2 * 1024 * 1024])?;
+
+    // the file is left untracked, with a warning, rather than blocking the mutation
+    let result = DescribeRevision {
+        id: revs::working_copy(),
+        new_description: "wip".to_owned(),
+        reset_author: false,
+    }
+    .execute_unboxed(&mut ws)
+    .await?;
+    let MutationResult::Updated { new_status } = result else {
+        panic!("expected Updated, got {result:?}");
+    };
+    assert_eq!(new_status.oversized_files.len(), 1);
+    assert_eq!(new_status.oversized_files[0].path.repo_path, "large.bin");
+    assert_eq!(new_status.oversized_files[0].size, 2 * 1024 * 1024);
+    assert_eq!(new_status.oversized_files[0].max_size, 1024 * 1024);
+    let rev = queries::query_revision(&ws, revs::working_copy()).await?;
+    assert_matches!(rev, RevResult::Detail { changes, .. } if !changes.iter().any(|change| change.path.repo_path == "large.bin"));
+
+    fs::remove_file(repo.path().join("large.bin"))?;
+
+    let result = DescribeRevision {
+        id: revs::working_copy(),
+        new_description: "wip2".to_owned(),
+        reset_author: false,
+    }
+    .execute_unboxed(&mut ws)
+    .await?;
+    assert_matches!(
+        result,
+        MutationResult::Updated { new_status } if new_status.oversized_files.is_empty()
+    );
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn duplicate_revisions() -> Result<()> {
     let repo = mkrepo();
@@ -314,6 +392,67 @@ async fn move_changes_single_path() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn move_revision() -> Result<()> {
+    let repo = mkrepo();
+
+    let mut session = WorkerSession::default();
+    let mut ws = session.load_directory(repo.path())?;
+
+    let page = queries::query_log(&ws, "@+", 1)?;
+    assert_eq!(0, page.rows.len());
+
+    let result = MoveRevision {
+        id: revs::resolve_conflict(),
+        parent_ids: vec![revs::working_copy()],
+    }
+    .execute_unboxed(&mut ws)
+    .await?;
+    assert_matches!(result, MutationResult::Updated { .. });
+
+    let page = queries::query_log(&ws, "@+", 2)?;
+    assert_eq!(1, page.rows.len());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn move_revision_onto_itself() -> Result<()> {
+    let repo = mkrepo();
+
+    let mut session = WorkerSession::default();
+    let mut ws = session.load_directory(repo.path())?;
+
+    let result = MoveRevision {
+        id: revs::resolve_conflict(),
+        parent_ids: vec![revs::resolve_conflict()],
+    }
+    .execute_unboxed(&mut ws)
+    .await?;
+    assert_matches!(result, MutationResult::PreconditionError { .. });
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn insert_revision_immutable() -> Result<()> {
+    let repo = mkrepo();
+
+    let mut session = WorkerSession::default();
+    let mut ws = session.load_directory(repo.path())?;
+
+    let result = InsertRevision {
+        after_id: revs::working_copy(),
+        before_id: revs::main_bookmark(),
+        id: revs::resolve_conflict(),
+    }
+    .execute_unboxed(&mut ws)
+    .await?;
+    assert_matches!(result, MutationResult::PreconditionError { .. });
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn move_source() -> Result<()> {
     let repo = mkrepo();
@@ -1366,6 +1505,1326 @@ async fn move_hunk_second_of_two_hunks() -> anyhow::Result<()> {
     Ok(())
 }
 
-// XXX missing tests for:
-// - branch/ref mutations
-// - git interop
+async fn read_hunk_test_file(ws: &WorkspaceSession<'_>, rev_id: &RevId) -> Result<String> {
+    use jj_lib::repo::Repo;
+
+    let commit = get_rev(ws, rev_id)?;
+    let repo_path = jj_lib::repo_path::RepoPath::from_internal_string("hunk_test.txt")?;
+    match commit.tree().path_value(repo_path)?.into_resolved() {
+        Ok(Some(jj_lib::backend::TreeValue::File { id, .. })) => {
+            let mut reader = ws.repo().store().read_file(repo_path, &id).await?;
+            let mut content = Vec::new();
+            reader.read_to_end(&mut content).await?;
+            Ok(String::from_utf8_lossy(&content).into_owned())
+        }
+        _ => anyhow::bail!("Expected hunk_test.txt to be a file"),
+    }
+}
+
+fn hunk_test_path() -> TreePath {
+    TreePath {
+        repo_path: "hunk_test.txt".to_owned(),
+        relative_path: "".into(),
+    }
+}
+
+#[tokio::test]
+async fn split_revision_hunk_before() -> Result<()> {
+    let repo = mkrepo();
+    let mut session = WorkerSession::default();
+    let mut ws = session.load_directory(repo.path())?;
+
+    // hunk_child_multi has two hunks: line2->changed2 and line4->changed4
+    let result = SplitRevision {
+        id: revs::hunk_child_multi(),
+        paths: vec![],
+        hunks: vec![FileHunk {
+            path: hunk_test_path(),
+            hunk: ChangeHunk {
+                location: HunkLocation {
+                    from_file: FileRange { start: 3, len: 3 },
+                    to_file: FileRange { start: 3, len: 3 },
+                },
+                lines: MultilineString {
+                    lines: vec![
+                        " line3".to_owned(),
+                        "-line4".to_owned(),
+                        "+changed4".to_owned(),
+                        " line5".to_owned(),
+                    ],
+                },
+            },
+        }],
+        placement: SplitPlacement::Before,
+    }
+    .execute_unboxed(&mut ws)
+    .await?;
+    let new_rev = match result {
+        MutationResult::UpdatedSelection { new_selection, .. } => new_selection,
+        other => panic!("Expected UpdatedSelection, got {other:?}"),
+    };
+
+    assert_eq!(revs::hunk_base().commit.hex, new_rev.parent_ids[0].hex);
+    assert_eq!(
+        "line1\nline2\nline3\nchanged4\nline5\n",
+        read_hunk_test_file(&ws, &new_rev.id).await?
+    );
+
+    // the original keeps all of its changes, now on top of the new revision
+    let original = get_rev(&ws, &revs::hunk_child_multi())?;
+    assert_eq!(new_rev.id.commit.hex, original.parent_ids()[0].hex());
+    assert_eq!(
+        "line1\nchanged2\nline3\nchanged4\nline5\n",
+        read_hunk_test_file(&ws, &revs::hunk_child_multi()).await?
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn split_revision_file_after() -> Result<()> {
+    let repo = mkrepo();
+    let mut session = WorkerSession::default();
+    let mut ws = session.load_directory(repo.path())?;
+
+    CreateRef {
+        id: revs::hunk_child_single(),
+        r#ref: local_bookmark("split"),
+    }
+    .execute_unboxed(&mut ws)
+    .await?;
+
+    let result = SplitRevision {
+        id: revs::hunk_child_single(),
+        paths: vec![hunk_test_path()],
+        hunks: vec![],
+        placement: SplitPlacement::After,
+    }
+    .execute_unboxed(&mut ws)
+    .await?;
+    let new_rev = match result {
+        MutationResult::UpdatedSelection { new_selection, .. } => new_selection,
+        other => panic!("Expected UpdatedSelection, got {other:?}"),
+    };
+
+    // the original is left with nothing, and the new revision takes over its child
+    let original = get_rev(&ws, &revs::hunk_child_single())?;
+    assert_eq!(original.id().hex(), new_rev.parent_ids[0].hex);
+    assert_eq!(
+        "line1\nline2\nline3\nline4\nline5\n",
+        read_hunk_test_file(&ws, &revs::hunk_child_single()).await?
+    );
+    assert_eq!(
+        "line1\nmodified2\nline3\nline4\nline5\n",
+        read_hunk_test_file(&ws, &new_rev.id).await?
+    );
+
+    let grandchild = get_rev(&ws, &revs::hunk_grandchild())?;
+    assert_eq!(
+        vec![new_rev.id.commit.hex.clone()],
+        grandchild
+            .parent_ids()
+            .iter()
+            .map(|id| id.hex())
+            .collect::<Vec<_>>()
+    );
+
+    // the original keeps its description, but its bookmark moves to the new, undescribed change,
+    // which has the files the bookmark pointed at before
+    assert_ne!("", original.description());
+    assert!(new_rev.description.lines[0].is_empty());
+    let bookmark = ws.view().get_local_bookmark(RefName::new("split"));
+    assert_eq!(
+        Some(new_rev.id.commit.hex),
+        bookmark.as_normal().map(|id| id.hex())
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn split_revision_file_sibling() -> Result<()> {
+    let repo = mkrepo();
+    let mut session = WorkerSession::default();
+    let mut ws = session.load_directory(repo.path())?;
+
+    let result = SplitRevision {
+        id: revs::hunk_child_single(),
+        paths: vec![hunk_test_path()],
+        hunks: vec![],
+        placement: SplitPlacement::Sibling,
+    }
+    .execute_unboxed(&mut ws)
+    .await?;
+    let new_rev = match result {
+        MutationResult::UpdatedSelection { new_selection, .. } => new_selection,
+        other => panic!("Expected UpdatedSelection, got {other:?}"),
+    };
+
+    let original = get_rev(&ws, &revs::hunk_child_single())?;
+    assert_eq!(revs::hunk_base().commit.hex, new_rev.parent_ids[0].hex);
+    assert_eq!(revs::hunk_base().commit.hex, original.parent_ids()[0].hex());
+
+    // the child merges both halves, so its contents are unchanged
+    let grandchild = get_rev(&ws, &revs::hunk_grandchild())?;
+    assert_eq!(
+        vec![original.id().hex(), new_rev.id.commit.hex.clone()],
+        grandchild
+            .parent_ids()
+            .iter()
+            .map(|id| id.hex())
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(
+        "line1\nmodified2\ngrandchild3\nline4\nline5\n",
+        read_hunk_test_file(&ws, &revs::hunk_grandchild()).await?
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn split_revision_nothing_selected() -> Result<()> {
+    let repo = mkrepo();
+    let mut session = WorkerSession::default();
+    let mut ws = session.load_directory(repo.path())?;
+
+    let result = SplitRevision {
+        id: revs::hunk_child_single(),
+        paths: vec![],
+        hunks: vec![],
+        placement: SplitPlacement::Before,
+    }
+    .execute_unboxed(&mut ws)
+    .await?;
+    assert_matches!(result, MutationResult::PreconditionError { .. });
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn squash_revision_into_parent() -> Result<()> {
+    let repo = mkrepo();
+    let mut session = WorkerSession::default();
+    let mut ws = session.load_directory(repo.path())?;
+
+    let result = SquashRevision {
+        from_id: revs::hunk_child_single(),
+        to_id: revs::hunk_base().commit,
+        message: SquashMessage::Custom {
+            description: "squashed".to_owned(),
+        },
+    }
+    .execute_unboxed(&mut ws)
+    .await?;
+    assert_matches!(result, MutationResult::Updated { .. });
+
+    let base = get_rev(&ws, &revs::hunk_base())?;
+    assert_eq!("squashed", base.description());
+    assert_eq!(
+        "line1\nmodified2\nline3\nline4\nline5\n",
+        read_hunk_test_file(&ws, &revs::hunk_base()).await?
+    );
+
+    // the emptied source is abandoned in the same operation, and its child moves onto the destination
+    let page = queries::query_log(&ws, "all()", 100)?;
+    assert_eq!(18, page.rows.len());
+    let grandchild = get_rev(&ws, &revs::hunk_grandchild())?;
+    assert_eq!(base.id(), &grandchild.parent_ids()[0]);
+    assert_eq!(
+        "line1\nmodified2\ngrandchild3\nline4\nline5\n",
+        read_hunk_test_file(&ws, &revs::hunk_grandchild()).await?
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn squash_revision_into_sibling() -> Result<()> {
+    let repo = mkrepo();
+    let mut session = WorkerSession::default();
+    let mut ws = session.load_directory(repo.path())?;
+
+    let sibling_description = get_rev(&ws, &revs::hunk_sibling())?
+        .description()
+        .to_owned();
+
+    let result = SquashRevision {
+        from_id: revs::hunk_child_multi(),
+        to_id: revs::hunk_sibling().commit,
+        message: SquashMessage::Destination,
+    }
+    .execute_unboxed(&mut ws)
+    .await?;
+    assert_matches!(result, MutationResult::Updated { .. });
+
+    let page = queries::query_log(&ws, "all()", 100)?;
+    assert_eq!(18, page.rows.len());
+    assert_eq!(
+        sibling_description,
+        get_rev(&ws, &revs::hunk_sibling())?.description()
+    );
+    assert_eq!(
+        "line1\nchanged2\nline3\nchanged4\nline5\nnew6\nnew7\nnew8\n",
+        read_hunk_test_file(&ws, &revs::hunk_sibling()).await?
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn squash_revision_into_itself() -> Result<()> {
+    let repo = mkrepo();
+    let mut session = WorkerSession::default();
+    let mut ws = session.load_directory(repo.path())?;
+
+    let result = SquashRevision {
+        from_id: revs::hunk_child_single(),
+        to_id: revs::hunk_child_single().commit,
+        message: SquashMessage::Combined,
+    }
+    .execute_unboxed(&mut ws)
+    .await?;
+    assert_matches!(result, MutationResult::PreconditionError { .. });
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn absorb_changes() -> Result<()> {
+    let repo = mkrepo();
+    let mut session = WorkerSession::default();
+    let mut ws = session.load_directory(repo.path())?;
+
+    // both of hunk_child_multi's hunks change lines which were last touched by hunk_base
+    let result = AbsorbChanges {
+        from_id: revs::hunk_child_multi(),
+        paths: vec![],
+    }
+    .execute_unboxed(&mut ws)
+    .await?;
+    match result {
+        MutationResult::UpdatedAbsorb {
+            absorbed,
+            skipped_paths,
+            ..
+        } => {
+            assert_eq!(1, absorbed.len());
+            assert_eq!(
+                revs::hunk_base().change.hex,
+                absorbed[0].revision.id.change.hex
+            );
+            assert_eq!(1, absorbed[0].changes.len());
+            assert_eq!(2, absorbed[0].changes[0].hunks.len());
+            assert!(skipped_paths.is_empty());
+        }
+        other => panic!("Expected UpdatedAbsorb, got {other:?}"),
+    }
+
+    assert_eq!(
+        "line1\nchanged2\nline3\nchanged4\nline5\n",
+        read_hunk_test_file(&ws, &revs::hunk_base()).await?
+    );
+
+    // the emptied source is abandoned
+    let page = queries::query_log(&ws, "all()", 100)?;
+    assert_eq!(18, page.rows.len());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn absorb_changes_without_destination() -> Result<()> {
+    let repo = mkrepo();
+    let mut session = WorkerSession::default();
+    let mut ws = session.load_directory(repo.path())?;
+
+    // hunk_base adds a new file, which no ancestor has touched
+    let result = AbsorbChanges {
+        from_id: revs::hunk_base(),
+        paths: vec![hunk_test_path()],
+    }
+    .execute_unboxed(&mut ws)
+    .await?;
+    assert_matches!(result, MutationResult::PreconditionError { .. });
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn reorder_revisions() -> Result<()> {
+    let repo = mkrepo();
+    let mut session = WorkerSession::default();
+    let mut ws = session.load_directory(repo.path())?;
+
+    // stack hunk_child_single on top of its sibling instead of beside it
+    let result = ReorderRevisions {
+        base: revs::hunk_base(),
+        order: vec![revs::hunk_sibling(), revs::hunk_child_single()],
+    }
+    .execute_unboxed(&mut ws)
+    .await?;
+    let revisions = match result {
+        MutationResult::UpdatedReorder { revisions, .. } => revisions,
+        other => panic!("Expected UpdatedReorder, got {other:?}"),
+    };
+    assert_eq!(2, revisions.len());
+    assert!(
+        revisions
+            .iter()
+            .all(|revision| !revision.revision.has_conflict && revision.conflicted_paths.is_empty())
+    );
+
+    let sibling = get_rev(&ws, &revs::hunk_sibling())?;
+    let child = get_rev(&ws, &revs::hunk_child_single())?;
+    assert_eq!(sibling.id(), &child.parent_ids()[0]);
+    assert_eq!(
+        "line1\nmodified2\nline3\nline4\nline5\nnew6\nnew7\nnew8\n",
+        read_hunk_test_file(&ws, &revs::hunk_child_single()).await?
+    );
+
+    // the unlisted grandchild follows its parent
+    let grandchild = get_rev(&ws, &revs::hunk_grandchild())?;
+    assert_eq!(child.id(), &grandchild.parent_ids()[0]);
+    assert_eq!(
+        "line1\nmodified2\ngrandchild3\nline4\nline5\nnew6\nnew7\nnew8\n",
+        read_hunk_test_file(&ws, &revs::hunk_grandchild()).await?
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn reorder_revisions_reports_conflicts() -> Result<()> {
+    let repo = mkrepo();
+    let mut session = WorkerSession::default();
+    let mut ws = session.load_directory(repo.path())?;
+
+    // both revisions change line2
+    let result = ReorderRevisions {
+        base: revs::hunk_base(),
+        order: vec![revs::hunk_child_single(), revs::hunk_child_multi()],
+    }
+    .execute_unboxed(&mut ws)
+    .await?;
+    let revisions = match result {
+        MutationResult::UpdatedReorder { revisions, .. } => revisions,
+        other => panic!("Expected UpdatedReorder, got {other:?}"),
+    };
+    assert!(revisions[0].conflicted_paths.is_empty());
+    assert!(revisions[1].revision.has_conflict);
+    assert_eq!(
+        vec!["hunk_test.txt"],
+        revisions[1]
+            .conflicted_paths
+            .iter()
+            .map(|path| path.repo_path.as_str())
+            .collect::<Vec<_>>()
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn reorder_revisions_moves_unlisted_descendants_to_top() -> Result<()> {
+    let repo = mkrepo();
+    let mut session = WorkerSession::default();
+    let mut ws = session.load_directory(repo.path())?;
+
+    // hunk_base -> hunk_child_single -> hunk_grandchild -> top
+    let top = match (CreateRevision {
+        parent_ids: vec![revs::hunk_grandchild()],
+    })
+    .execute_unboxed(&mut ws)
+    .await?
+    {
+        MutationResult::UpdatedSelection { new_selection, .. } => new_selection.id,
+        other => panic!("Expected UpdatedSelection, got {other:?}"),
+    };
+
+    let result = ReorderRevisions {
+        base: revs::hunk_base(),
+        order: vec![revs::hunk_grandchild(), revs::hunk_child_single()],
+    }
+    .execute_unboxed(&mut ws)
+    .await?;
+    assert_matches!(result, MutationResult::UpdatedReorder { .. });
+
+    // the unlisted revision follows the stack rather than its old parent
+    let base = get_rev(&ws, &revs::hunk_base())?;
+    let grandchild = get_rev(&ws, &revs::hunk_grandchild())?;
+    let child = get_rev(&ws, &revs::hunk_child_single())?;
+    let top = get_rev(&ws, &top)?;
+    assert_eq!(base.id(), &grandchild.parent_ids()[0]);
+    assert_eq!(grandchild.id(), &child.parent_ids()[0]);
+    assert_eq!(vec![child.id().clone()], top.parent_ids());
+    assert_eq!(
+        "line1\nmodified2\ngrandchild3\nline4\nline5\n",
+        read_hunk_test_file(&ws, &revs::hunk_child_single()).await?
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn reorder_revisions_already_in_order() -> Result<()> {
+    let repo = mkrepo();
+    let mut session = WorkerSession::default();
+    let mut ws = session.load_directory(repo.path())?;
+
+    let result = ReorderRevisions {
+        base: revs::hunk_base(),
+        order: vec![revs::hunk_child_single(), revs::hunk_grandchild()],
+    }
+    .execute_unboxed(&mut ws)
+    .await?;
+    assert_matches!(result, MutationResult::Unchanged);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn reorder_revisions_onto_descendant() -> Result<()> {
+    let repo = mkrepo();
+    let mut session = WorkerSession::default();
+    let mut ws = session.load_directory(repo.path())?;
+
+    let result = ReorderRevisions {
+        base: revs::hunk_grandchild(),
+        order: vec![revs::hunk_child_single()],
+    }
+    .execute_unboxed(&mut ws)
+    .await?;
+    assert_matches!(result, MutationResult::PreconditionError { .. });
+
+    Ok(())
+}
+
+fn local_bookmark(name: &str) -> StoreRef {
+    StoreRef::LocalBookmark {
+        branch_name: name.to_owned(),
+        has_conflict: false,
+        is_synced: true,
+        tracking_remotes: vec![],
+        available_remotes: 0,
+        potential_remotes: 0,
+    }
+}
+
+fn has_ref(rev: &RevResult, name: &str) -> bool {
+    matches!(rev, RevResult::Detail { header, .. } if header.refs.iter().any(|r#ref| match r#ref {
+        StoreRef::LocalBookmark { branch_name, .. } => branch_name == name,
+        StoreRef::Tag { tag_name } => tag_name == name,
+        _ => false,
+    }))
+}
+
+#[tokio::test]
+async fn bookmark_lifecycle() -> Result<()> {
+    let repo = mkrepo();
+
+    let mut session = WorkerSession::default();
+    let mut ws = session.load_directory(repo.path())?;
+
+    let result = CreateRef {
+        id: revs::resolve_conflict(),
+        r#ref: local_bookmark("feature"),
+    }
+    .execute_unboxed(&mut ws)
+    .await?;
+    assert_matches!(result, MutationResult::Updated { .. });
+    let rev = queries::query_revision(&ws, revs::resolve_conflict()).await?;
+    assert!(has_ref(&rev, "feature"));
+
+    let result = CreateRef {
+        id: revs::working_copy(),
+        r#ref: local_bookmark("feature"),
+    }
+    .execute_unboxed(&mut ws)
+    .await?;
+    assert_matches!(result, MutationResult::PreconditionError { .. });
+
+    let result = RenameBranch {
+        r#ref: local_bookmark("feature"),
+        new_name: "renamed".to_owned(),
+    }
+    .execute_unboxed(&mut ws)
+    .await?;
+    assert_matches!(result, MutationResult::Updated { .. });
+
+    let result = MoveRef {
+        r#ref: local_bookmark("renamed"),
+        to_id: revs::working_copy(),
+    }
+    .execute_unboxed(&mut ws)
+    .await?;
+    assert_matches!(result, MutationResult::Updated { .. });
+    let rev = queries::query_revision(&ws, revs::working_copy()).await?;
+    assert!(has_ref(&rev, "renamed"));
+
+    let result = DeleteRef {
+        r#ref: local_bookmark("renamed"),
+    }
+    .execute_unboxed(&mut ws)
+    .await?;
+    assert_matches!(result, MutationResult::Updated { .. });
+    let rev = queries::query_revision(&ws, revs::working_copy()).await?;
+    assert!(!has_ref(&rev, "renamed"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn tag_lifecycle() -> Result<()> {
+    let repo = mkrepo();
+
+    let mut session = WorkerSession::default();
+    let mut ws = session.load_directory(repo.path())?;
+
+    let tag = StoreRef::Tag {
+        tag_name: "v1".to_owned(),
+    };
+
+    let result = CreateRef {
+        id: revs::resolve_conflict(),
+        r#ref: tag.clone(),
+    }
+    .execute_unboxed(&mut ws)
+    .await?;
+    assert_matches!(result, MutationResult::Updated { .. });
+
+    let result = MoveRef {
+        r#ref: tag.clone(),
+        to_id: revs::working_copy(),
+    }
+    .execute_unboxed(&mut ws)
+    .await?;
+    assert_matches!(result, MutationResult::Updated { .. });
+    let rev = queries::query_revision(&ws, revs::working_copy()).await?;
+    assert!(has_ref(&rev, "v1"));
+
+    let result = DeleteRef { r#ref: tag }.execute_unboxed(&mut ws).await?;
+    assert_matches!(result, MutationResult::Updated { .. });
+    let rev = queries::query_revision(&ws, revs::working_copy()).await?;
+    assert!(!has_ref(&rev, "v1"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn cancelled_mutation() -> Result<()> {
+    let repo = mkrepo();
+
+    let mut session = WorkerSession::default();
+    let mut ws = session.load_directory(repo.path())?;
+
+    let id = ws.session.cancellation.begin();
+    ws.session.cancellation.cancel(id);
+    let result = CreateRef {
+        id: revs::resolve_conflict(),
+        r#ref: local_bookmark("cancelled"),
+    }
+    .execute_unboxed(&mut ws)
+    .await;
+    assert!(result.is_err_and(|err| err.is::<Cancelled>()));
+
+    ws.session.cancellation.begin();
+    let rev = queries::query_revision(&ws, revs::resolve_conflict()).await?;
+    assert!(!has_ref(&rev, "cancelled"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn cancellation_of_earlier_operation() -> Result<()> {
+    let repo = mkrepo();
+
+    let mut session = WorkerSession::default();
+    let mut ws = session.load_directory(repo.path())?;
+
+    // a request for an operation which has finished doesn't stop the next one
+    let earlier_id = ws.session.cancellation.begin();
+    ws.session.cancellation.begin();
+    ws.session.cancellation.cancel(earlier_id);
+    let result = CreateRef {
+        id: revs::resolve_conflict(),
+        r#ref: local_bookmark("uncancelled"),
+    }
+    .execute_unboxed(&mut ws)
+    .await?;
+    assert_matches!(result, MutationResult::Updated { .. });
+
+    Ok(())
+}
+
+fn git(args: &[&str]) -> Result<bool> {
+    Ok(Command::new("git").args(args).output()?.status.success())
+}
+
+/// replaces the test repo's github remote with a local bare repo
+fn mkremote(repo: &TempDir) -> Result<TempDir> {
+    let remote = tempdir()?;
+    let remote_path = remote.path().to_str().unwrap();
+    let store_path = repo.path().join(".jj/repo/store/git");
+    let store_dir = format!("--git-dir={}", store_path.to_str().unwrap());
+    assert!(git(&["init", "--bare", remote_path])?);
+    assert!(git(&[
+        &store_dir,
+        "remote",
+        "set-url",
+        "origin",
+        remote_path
+    ])?);
+    Ok(remote)
+}
+
+#[tokio::test]
+async fn git_push_and_fetch() -> Result<()> {
+    let repo = mkrepo();
+    let remote = mkremote(&repo)?;
+    let remote_dir = format!("--git-dir={}", remote.path().to_str().unwrap());
+
+    let mut session = WorkerSession::default();
+    let mut ws = session.load_directory(repo.path())?;
+
+    let result = CreateRef {
+        id: revs::main_bookmark(),
+        r#ref: local_bookmark("pushed"),
+    }
+    .execute_unboxed(&mut ws)
+    .await?;
+    assert_matches!(result, MutationResult::Updated { .. });
+
+    let result = GitPush::RemoteBookmark {
+        remote_name: "origin".to_owned(),
+        branch_ref: local_bookmark("pushed"),
+    }
+    .execute_unboxed(&mut ws)
+    .await?;
+    assert_matches!(result, MutationResult::Updated { .. });
+    assert!(git(&[
+        &remote_dir,
+        "rev-parse",
+        "--verify",
+        "refs/heads/pushed"
+    ])?);
+
+    let result = GitPush::RemoteBookmark {
+        remote_name: "origin".to_owned(),
+        branch_ref: local_bookmark("pushed"),
+    }
+    .execute_unboxed(&mut ws)
+    .await?;
+    assert_matches!(result, MutationResult::PreconditionError { .. });
+
+    let result = GitFetch::AllBookmarks {
+        remote_name: "origin".to_owned(),
+    }
+    .execute_unboxed(&mut ws)
+    .await?;
+    assert_matches!(
+        result,
+        MutationResult::Updated { .. } | MutationResult::Unchanged
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn git_push_keeps_successful_remotes() -> Result<()> {
+    let repo = mkrepo();
+    let _remote = mkremote(&repo)?;
+    let backup = tempdir()?;
+    let backup_path = backup.path().to_str().unwrap().to_owned();
+    let store_path = repo.path().join(".jj/repo/store/git");
+    let store_dir = format!("--git-dir={}", store_path.to_str().unwrap());
+    assert!(git(&["init", "--bare", &backup_path])?);
+    assert!(git(&[&store_dir, "remote", "add", "backup", &backup_path])?);
+
+    let mut session = WorkerSession::default();
+    let mut ws = session.load_directory(repo.path())?;
+
+    CreateRef {
+        id: revs::hunk_base(),
+        r#ref: local_bookmark("pushed"),
+    }
+    .execute_unboxed(&mut ws)
+    .await?;
+    for remote_name in ["origin", "backup"] {
+        let result = GitPush::RemoteBookmark {
+            remote_name: remote_name.to_owned(),
+            branch_ref: local_bookmark("pushed"),
+        }
+        .execute_unboxed(&mut ws)
+        .await?;
+        assert_matches!(result, MutationResult::Updated { .. });
+    }
+
+    // move the bookmark, then make the second remote unreachable
+    MoveRef {
+        r#ref: local_bookmark("pushed"),
+        to_id: revs::hunk_child_single(),
+    }
+    .execute_unboxed(&mut ws)
+    .await?;
+    drop(backup);
+
+    let result = GitPush::AllRemotes {
+        branch_ref: local_bookmark("pushed"),
+    }
+    .execute_unboxed(&mut ws)
+    .await?;
+    assert_matches!(result, MutationResult::UpdatedWithErrors { message, .. } if message.contains("backup") && !message.contains("origin"));
+
+    // origin's tracking bookmark was recorded, so only the failed remote is retried
+    let result = GitPush::AllRemotes {
+        branch_ref: local_bookmark("pushed"),
+    }
+    .execute_unboxed(&mut ws)
+    .await?;
+    assert_matches!(result, MutationResult::PreconditionError { message } if message.contains("backup") && !message.contains("origin"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn git_push_unready_commits() -> Result<()> {
+    let repo = mkrepo();
+    let _remote = mkremote(&repo)?;
+
+    let mut session = WorkerSession::default();
+    let mut ws = session.load_directory(repo.path())?;
+
+    // resolve_conflict is fine, but its parent has conflicts and no description
+    CreateRef {
+        id: revs::resolve_conflict(),
+        r#ref: local_bookmark("pushed"),
+    }
+    .execute_unboxed(&mut ws)
+    .await?;
+    let result = GitPush::RemoteBookmark {
+        remote_name: "origin".to_owned(),
+        branch_ref: local_bookmark("pushed"),
+    }
+    .execute_unboxed(&mut ws)
+    .await?;
+    assert_matches!(result, MutationResult::PreconditionError { message } if message.contains("conflicts") && message.contains("no description"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn git_push_revision() -> Result<()> {
+    let repo = mkrepo();
+    let remote = mkremote(&repo)?;
+    let remote_dir = format!("--git-dir={}", remote.path().to_str().unwrap());
+
+    let mut session = WorkerSession::default();
+    let mut ws = session.load_directory(repo.path())?;
+
+    let result = GitPush::Revision {
+        id: revs::main_bookmark(),
+        remote_name: "origin".to_owned(),
+    }
+    .execute_unboxed(&mut ws)
+    .await?;
+    assert_matches!(result, MutationResult::Updated { .. });
+
+    let rev = queries::query_revision(&ws, revs::main_bookmark()).await?;
+    let branch_name = match &rev {
+        RevResult::Detail { header, .. } => header.refs.iter().find_map(|r#ref| match r#ref {
+            StoreRef::LocalBookmark { branch_name, .. } if branch_name.starts_with("push-") => {
+                Some(branch_name.clone())
+            }
+            _ => None,
+        }),
+        _ => None,
+    }
+    .expect("push bookmark created");
+    assert!(git(&[
+        &remote_dir,
+        "rev-parse",
+        "--verify",
+        &format!("refs/heads/{branch_name}")
+    ])?);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn git_push_stack() -> Result<()> {
+    let repo = mkrepo();
+    let remote = mkremote(&repo)?;
+    let remote_dir = format!("--git-dir={}", remote.path().to_str().unwrap());
+
+    let mut session = WorkerSession::default();
+    let mut ws = session.load_directory(repo.path())?;
+
+    let result = GitPushStack {
+        head_id: revs::hunk_grandchild(),
+        remote_name: "origin".to_owned(),
+    }
+    .execute_unboxed(&mut ws)
+    .await?;
+    let pushes = match result {
+        MutationResult::UpdatedPushes { pushes, .. } => pushes,
+        _ => panic!("expected UpdatedPushes, got {result:?}"),
+    };
+
+    // hunk_base -> hunk_child_single -> hunk_grandchild, from the bottom up
+    assert_eq!(3, pushes.len());
+    assert_eq!(
+        revs::hunk_base().change.hex,
+        pushes[0].revision.id.change.hex
+    );
+    assert_eq!(
+        revs::hunk_grandchild().change.hex,
+        pushes[2].revision.id.change.hex
+    );
+    for push in &pushes {
+        assert!(push.branch_name.starts_with("push-"));
+        assert_matches!(
+            push.outcome,
+            PushOutcome::Pushed {
+                action: PushAction::Create
+            }
+        );
+        assert!(git(&[
+            &remote_dir,
+            "rev-parse",
+            "--verify",
+            &format!("refs/heads/{}", push.branch_name)
+        ])?);
+    }
+
+    // everything is already on the remote
+    let result = GitPushStack {
+        head_id: revs::hunk_grandchild(),
+        remote_name: "origin".to_owned(),
+    }
+    .execute_unboxed(&mut ws)
+    .await?;
+    assert_matches!(result, MutationResult::Unchanged);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn git_push_stack_unpublishable() -> Result<()> {
+    let repo = mkrepo();
+    let _remote = mkremote(&repo)?;
+
+    let mut session = WorkerSession::default();
+    let mut ws = session.load_directory(repo.path())?;
+
+    // conflict_bookmark has no description, as well as the conflict
+    let result = GitPushStack {
+        head_id: revs::conflict_bookmark(),
+        remote_name: "origin".to_owned(),
+    }
+    .execute_unboxed(&mut ws)
+    .await?;
+    assert_matches!(result, MutationResult::PreconditionError { .. });
+
+    Ok(())
+}
+
+fn git_remotes(ws: &WorkspaceSession) -> Result<Vec<String>> {
+    match ws.format_config()? {
+        RepoConfig::Workspace { git_remotes, .. } => Ok(git_remotes),
+        _ => panic!("expected workspace config"),
+    }
+}
+
+#[tokio::test]
+async fn manage_remotes() -> Result<()> {
+    let repo = mkrepo();
+    let mut session = WorkerSession::default();
+
+    // each step reloads the workspace, as the frontend does, to see the new git config
+    {
+        let mut ws = session.load_directory(repo.path())?;
+        assert_eq!(vec!["origin", "second"], git_remotes(&ws)?);
+
+        let result = AddRemote {
+            remote_name: "third".to_owned(),
+            url: "https://example.com/third.git".to_owned(),
+            push_url: None,
+        }
+        .execute_unboxed(&mut ws)
+        .await?;
+        assert_matches!(
+            result,
+            MutationResult::Updated { .. } | MutationResult::Unchanged
+        );
+    }
+
+    {
+        let mut ws = session.load_directory(repo.path())?;
+        assert_eq!(vec!["origin", "second", "third"], git_remotes(&ws)?);
+
+        let result = AddRemote {
+            remote_name: "third".to_owned(),
+            url: "https://example.com/third.git".to_owned(),
+            push_url: None,
+        }
+        .execute_unboxed(&mut ws)
+        .await?;
+        assert_matches!(result, MutationResult::PreconditionError { .. });
+
+        let result = RenameRemote {
+            old_name: "second".to_owned(),
+            new_name: "renamed".to_owned(),
+        }
+        .execute_unboxed(&mut ws)
+        .await?;
+        assert_matches!(result, MutationResult::Updated { .. });
+        assert_eq!(
+            0,
+            ws.view()
+                .remote_bookmarks(RemoteName::new("second"))
+                .count()
+        );
+        assert_ne!(
+            0,
+            ws.view()
+                .remote_bookmarks(RemoteName::new("renamed"))
+                .count()
+        );
+    }
+
+    {
+        let mut ws = session.load_directory(repo.path())?;
+        assert_eq!(vec!["origin", "renamed", "third"], git_remotes(&ws)?);
+
+        let result = RemoveRemote {
+            remote_name: "renamed".to_owned(),
+        }
+        .execute_unboxed(&mut ws)
+        .await?;
+        assert_matches!(result, MutationResult::Updated { .. });
+        assert_eq!(
+            0,
+            ws.view()
+                .remote_bookmarks(RemoteName::new("renamed"))
+                .count()
+        );
+
+        let result = SetRemoteUrl {
+            remote_name: "third".to_owned(),
+            url: "https://example.com/moved.git".to_owned(),
+            push_url: None,
+        }
+        .execute_unboxed(&mut ws)
+        .await?;
+        assert_matches!(result, MutationResult::Unchanged);
+    }
+
+    let ws = session.load_directory(repo.path())?;
+    assert_eq!(vec!["origin", "third"], git_remotes(&ws)?);
+    let url = Command::new("git")
+        .arg(format!(
+            "--git-dir={}",
+            repo.path().join(".jj/repo/store/git").to_str().unwrap()
+        ))
+        .args(["remote", "get-url", "third"])
+        .output()?
+        .stdout;
+    assert_eq!(
+        "https://example.com/moved.git",
+        String::from_utf8(url)?.trim()
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn restore_hidden_revision_in_place() -> Result<()> {
+    let repo = mkrepo();
+
+    let mut session = WorkerSession::default();
+    let mut ws = session.load_directory(repo.path())?;
+
+    DescribeRevision {
+        id: revs::resolve_conflict(),
+        new_description: "renamed".to_owned(),
+        reset_author: false,
+    }
+    .execute_unboxed(&mut ws)
+    .await?;
+
+    let result = RestoreHiddenRevision {
+        id: revs::resolve_conflict(),
+        as_new_change: false,
+    }
+    .execute_unboxed(&mut ws)
+    .await?;
+    assert_matches!(result, MutationResult::UpdatedSelection { new_selection, .. } if new_selection.id.change.hex == revs::resolve_conflict().change.hex);
+
+    let renamed = queries::query_log(&ws, "description(renamed)", 1)?;
+    assert!(renamed.rows.is_empty());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn restore_hidden_revision_as_new_change() -> Result<()> {
+    let repo = mkrepo();
+
+    let mut session = WorkerSession::default();
+    let mut ws = session.load_directory(repo.path())?;
+
+    DescribeRevision {
+        id: revs::resolve_conflict(),
+        new_description: "renamed".to_owned(),
+        reset_author: false,
+    }
+    .execute_unboxed(&mut ws)
+    .await?;
+
+    let result = RestoreHiddenRevision {
+        id: revs::resolve_conflict(),
+        as_new_change: true,
+    }
+    .execute_unboxed(&mut ws)
+    .await?;
+    assert_matches!(result, MutationResult::UpdatedSelection { new_selection, .. } if new_selection.id.change.hex != revs::resolve_conflict().change.hex);
+
+    let renamed = queries::query_log(&ws, "description(renamed)", 1)?;
+    assert_eq!(1, renamed.rows.len());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn mutation_operation_tags() -> Result<()> {
+    let repo = mkrepo();
+
+    let mut session = WorkerSession::default();
+    let mut ws = session.load_directory(repo.path())?;
+
+    DescribeRevision {
+        id: revs::resolve_conflict(),
+        new_description: "renamed".to_owned(),
+        reset_author: false,
+    }
+    .execute_unboxed(&mut ws)
+    .await?;
+
+    let result = RestoreHiddenRevision {
+        id: revs::resolve_conflict(),
+        as_new_change: false,
+    }
+    .execute_unboxed(&mut ws)
+    .await?;
+    let MutationResult::UpdatedSelection { new_status, .. } = result else {
+        panic!("restore failed: {result:?}");
+    };
+    let tags = new_status.operation_tags;
+    assert_eq!(
+        Some("RestoreHiddenRevision"),
+        tags.get("gg.mutation").map(String::as_str)
+    );
+    assert!(tags["gg.changes"].contains(&revs::resolve_conflict().change.hex));
+    assert!(tags.contains_key("gg.version"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn abandon_revisions_operation_tags() -> Result<()> {
+    let repo = mkrepo();
+
+    let mut session = WorkerSession::default();
+    let mut ws = session.load_directory(repo.path())?;
+
+    let result = AbandonRevisions {
+        ids: vec![revs::resolve_conflict().commit],
+    }
+    .execute_unboxed(&mut ws)
+    .await?;
+    let MutationResult::Updated { new_status } = result else {
+        panic!("abandon failed: {result:?}");
+    };
+    let tags = new_status.operation_tags;
+    assert_eq!(
+        Some("AbandonRevisions"),
+        tags.get("gg.mutation").map(String::as_str)
+    );
+    assert!(tags["gg.changes"].contains(&revs::resolve_conflict().change.hex));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn snapshot_operation_tags() -> Result<()> {
+    let repo = mkrepo();
+
+    let mut session = WorkerSession::default();
+    let mut ws = session.load_directory(repo.path())?;
+
+    fs::write(repo.path().join("new.txt"), []).unwrap();
+    assert!(ws.import_and_snapshot(true).await?);
+
+    let tags = ws.format_status().operation_tags;
+    assert_eq!(
+        Some("SnapshotWorkingCopy"),
+        tags.get("gg.mutation").map(String::as_str)
+    );
+    assert!(tags["gg.changes"].contains(&revs::working_copy().change.hex));
+    assert!(tags.contains_key("gg.version"));
+
+    let operations = queries::query_operations(&ws, 0, 1)?.operations;
+    assert!(operations[0].is_snapshot);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn ref_operation_tags() -> Result<()> {
+    let repo = mkrepo();
+
+    let mut session = WorkerSession::default();
+    let mut ws = session.load_directory(repo.path())?;
+
+    let result = CreateRef {
+        id: revs::main_bookmark(),
+        r#ref: local_bookmark("tagged"),
+    }
+    .execute_unboxed(&mut ws)
+    .await?;
+    let MutationResult::Updated { new_status } = result else {
+        panic!("create ref failed: {result:?}");
+    };
+
+    // no commits changed, so there are no changes to record
+    let tags = new_status.operation_tags;
+    assert_eq!(
+        Some("CreateRef"),
+        tags.get("gg.mutation").map(String::as_str)
+    );
+    assert!(!tags.contains_key("gg.changes"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn restore_visible_revision() -> Result<()> {
+    let repo = mkrepo();
+
+    let mut session = WorkerSession::default();
+    let mut ws = session.load_directory(repo.path())?;
+
+    let result = RestoreHiddenRevision {
+        id: revs::resolve_conflict(),
+        as_new_change: false,
+    }
+    .execute_unboxed(&mut ws)
+    .await?;
+    assert_matches!(result, MutationResult::PreconditionError { .. });
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn restore_operation() -> Result<()> {
+    let repo = mkrepo();
+
+    let mut session = WorkerSession::default();
+    let mut ws = session.load_directory(repo.path())?;
+    let old_wc = ws.wc_id().clone();
+    let old_op = queries::query_operations(&ws, 0, 1)?
+        .operations
+        .remove(0)
+        .id;
+
+    fs::write(repo.path().join("new.txt"), []).unwrap();
+    ws.import_and_snapshot(true).await?;
+    assert_ne!(&old_wc, ws.wc_id());
+
+    let result = RestoreOperation { op_id: old_op }
+        .execute_unboxed(&mut ws)
+        .await?;
+    assert_matches!(result, MutationResult::UpdatedSelection { .. });
+    assert_eq!(&old_wc, ws.wc_id());
+    assert!(!repo.path().join("new.txt").exists());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn revert_operation() -> Result<()> {
+    let repo = mkrepo();
+
+    let mut session = WorkerSession::default();
+    let mut ws = session.load_directory(repo.path())?;
+
+    DescribeRevision {
+        id: revs::resolve_conflict(),
+        new_description: "renamed".to_owned(),
+        reset_author: false,
+    }
+    .execute_unboxed(&mut ws)
+    .await?;
+    let describe_op = queries::query_operations(&ws, 0, 1)?
+        .operations
+        .remove(0)
+        .id;
+
+    fs::write(repo.path().join("new.txt"), []).unwrap();
+    ws.import_and_snapshot(true).await?;
+
+    let result = RevertOperation { op_id: describe_op }
+        .execute_unboxed(&mut ws)
+        .await?;
+    assert_matches!(result, MutationResult::UpdatedSelection { .. });
+
+    let renamed = queries::query_log(&ws, "description(renamed)", 1)?;
+    assert!(renamed.rows.is_empty());
+    assert!(repo.path().join("new.txt").exists());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn redo_without_undo() -> Result<()> {
+    let repo = mkrepo();
+
+    let mut session = WorkerSession::default();
+    let mut ws = session.load_directory(repo.path())?;
+
+    let result = RedoOperation.execute_unboxed(&mut ws).await?;
+    assert_matches!(result, MutationResult::PreconditionError { .. });
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn redo_after_undo() -> Result<()> {
+    let repo = mkrepo();
+
+    let mut session = WorkerSession::default();
+    let mut ws = session.load_directory(repo.path())?;
+
+    fs::write(repo.path().join("new.txt"), []).unwrap();
+    ws.import_and_snapshot(true).await?;
+    let snapshot_wc = ws.wc_id().clone();
+
+    let result = UndoOperation.execute_unboxed(&mut ws).await?;
+    assert_matches!(result, MutationResult::UpdatedSelection { .. });
+    assert_ne!(&snapshot_wc, ws.wc_id());
+
+    // described as `jj redo` would, so that jj can continue the redo stack
+    let result = RedoOperation.execute_unboxed(&mut ws).await?;
+    assert_matches!(
+        result,
+        MutationResult::UpdatedSelection { new_status, .. }
+            if new_status.operation_description.starts_with("redo: restore to operation ")
+    );
+    assert_eq!(&snapshot_wc, ws.wc_id());
+
+    let result = RedoOperation.execute_unboxed(&mut ws).await?;
+    assert_matches!(result, MutationResult::PreconditionError { .. });
+
+    Ok(())
+}
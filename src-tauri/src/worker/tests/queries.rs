@@ -1,6 +1,9 @@
 use super::{mkrepo, revs};
-use crate::messages::{RevHeader, RevResult, StoreRef};
-use crate::worker::{WorkerSession, queries};
+use crate::messages::{
+    AbandonRevisions, BookmarkPush, CreateRef, DescribeRevision, EvolutionResult, GitPush,
+    PushAction, PushPlan, RevHeader, RevResult, StoreRef,
+};
+use crate::worker::{Mutation, WorkerSession, queries};
 use anyhow::Result;
 use assert_matches::assert_matches;
 
@@ -132,3 +135,181 @@ fn remotes_tracking_bookmark() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn evolution() -> Result<()> {
+    let repo = mkrepo();
+
+    let mut session = WorkerSession::default();
+    let mut ws = session.load_directory(repo.path())?;
+
+    DescribeRevision {
+        id: revs::resolve_conflict(),
+        new_description: "renamed".to_owned(),
+        reset_author: false,
+    }
+    .execute_unboxed(&mut ws)
+    .await?;
+
+    let result = queries::query_evolution(&ws, revs::resolve_conflict()).await?;
+    let EvolutionResult::Detail { entries } = result else {
+        panic!("change not found");
+    };
+    assert!(entries.len() >= 2);
+    assert_eq!(
+        revs::resolve_conflict().commit.hex,
+        entries[0].predecessor_ids[0].hex
+    );
+    assert_eq!(
+        revs::resolve_conflict().commit.hex,
+        entries[1].header.id.commit.hex
+    );
+    assert!(entries[0].operation.is_some());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn push_plan() -> Result<()> {
+    let repo = mkrepo();
+
+    let mut session = WorkerSession::default();
+    let mut ws = session.load_directory(repo.path())?;
+
+    let branch_ref = StoreRef::LocalBookmark {
+        branch_name: "planned".to_owned(),
+        has_conflict: false,
+        is_synced: true,
+        tracking_remotes: vec![],
+        available_remotes: 0,
+        potential_remotes: 0,
+    };
+    CreateRef {
+        id: revs::main_bookmark(),
+        r#ref: branch_ref.clone(),
+    }
+    .execute_unboxed(&mut ws)
+    .await?;
+
+    let plan = queries::query_push_plan(
+        &ws,
+        &GitPush::RemoteBookmark {
+            remote_name: "origin".to_owned(),
+            branch_ref,
+        },
+    )?;
+    assert_matches!(plan, PushPlan::Ready { remotes } if remotes.len() == 1 && matches!(
+        remotes[0].bookmarks.as_slice(),
+        [BookmarkPush { action: PushAction::Create, old_target: None, new_target: Some(_), .. }]
+    ));
+
+    Ok(())
+}
+
+// Operation Log Queries
+// =====================
+// The operation log, and the differences between operations, rather than the revisions at one.
+
+#[test]
+fn operations_paged() -> Result<()> {
+    let repo = mkrepo();
+
+    let mut session = WorkerSession::default();
+    let ws = session.load_directory(repo.path())?;
+
+    let first_page = queries::query_operations(&ws, 0, 2)?;
+    assert_eq!(2, first_page.operations.len());
+    assert!(first_page.has_more);
+    assert!(first_page.operations[0].is_current);
+    assert!(!first_page.operations[1].is_current);
+
+    let second_page = queries::query_operations(&ws, 1, 1)?;
+    assert_eq!(1, second_page.operations.len());
+    assert_eq!(
+        first_page.operations[1].id.hex,
+        second_page.operations[0].id.hex
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn operation_diff() -> Result<()> {
+    let repo = mkrepo();
+
+    let mut session = WorkerSession::default();
+    let mut ws = session.load_directory(repo.path())?;
+
+    DescribeRevision {
+        id: revs::resolve_conflict(),
+        new_description: "renamed".to_owned(),
+        reset_author: false,
+    }
+    .execute_unboxed(&mut ws)
+    .await?;
+
+    let describe_op = queries::query_operations(&ws, 0, 1)?
+        .operations
+        .remove(0)
+        .id;
+    let diff = queries::query_operation_diff(&ws, None, &describe_op)?;
+    assert!(diff.added.is_empty());
+    assert!(diff.abandoned.is_empty());
+    assert!(diff.rewritten.iter().any(|rewritten| {
+        rewritten.header.id.change.hex == revs::resolve_conflict().change.hex
+            && rewritten.old_ids.len() == 1
+            && rewritten.old_ids[0].hex == revs::resolve_conflict().commit.hex
+    }));
+
+    let renamed = queries::query_log(&ws, "description(renamed)", 1)?;
+    AbandonRevisions {
+        ids: vec![renamed.rows[0].revision.id.commit.clone()],
+    }
+    .execute_unboxed(&mut ws)
+    .await?;
+
+    let abandon_op = queries::query_operations(&ws, 0, 1)?
+        .operations
+        .remove(0)
+        .id;
+    let diff = queries::query_operation_diff(&ws, Some(&describe_op), &abandon_op)?;
+    assert!(
+        diff.abandoned
+            .iter()
+            .any(|header| header.id.change.hex == revs::resolve_conflict().change.hex)
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn operation_diff_rewritten_head() -> Result<()> {
+    let repo = mkrepo();
+
+    let mut session = WorkerSession::default();
+    let mut ws = session.load_directory(repo.path())?;
+
+    // the new head is unknown to the parent operation, but its ancestors are still visible
+    DescribeRevision {
+        id: revs::hunk_grandchild(),
+        new_description: "renamed".to_owned(),
+        reset_author: false,
+    }
+    .execute_unboxed(&mut ws)
+    .await?;
+
+    let describe_op = queries::query_operations(&ws, 0, 1)?
+        .operations
+        .remove(0)
+        .id;
+    let diff = queries::query_operation_diff(&ws, None, &describe_op)?;
+    assert!(diff.added.is_empty());
+    assert!(diff.abandoned.is_empty());
+    assert_eq!(1, diff.rewritten.len());
+    assert_eq!(
+        revs::hunk_grandchild().change.hex,
+        diff.rewritten[0].header.id.change.hex
+    );
+
+    Ok(())
+}
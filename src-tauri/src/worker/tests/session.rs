@@ -1,11 +1,20 @@
 use super::{mkid, mkrepo, revs};
 use crate::{
-    messages::{LogPage, RepoConfig, RevResult},
-    worker::{Session, SessionEvent, WorkerSession},
+    messages::{
+        AbandonRevisions, CreateRef, LogPage, MutationResult, RepoConfig, RepoStatus, RevResult,
+        StoreRef, WorkspaceSource,
+    },
+    worker::{Session, SessionEvent, WorkerSession, queries},
 };
 use anyhow::Result;
-use jj_lib::config::ConfigSource;
-use std::{path::PathBuf, sync::mpsc::channel};
+use jj_lib::{config::ConfigSource, object_id::ObjectId, ref_name::RefName};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+    sync::mpsc::channel,
+};
+use tempfile::{TempDir, tempdir};
 
 #[tokio::test]
 async fn start_and_stop() -> Result<()> {
@@ -335,6 +344,61 @@ async fn query_rev_not_found() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn mutate_historical_operation() -> Result<()> {
+    let repo = mkrepo();
+
+    let op_id = {
+        let mut session = WorkerSession::default();
+        let ws = session.load_directory(repo.path())?;
+        queries::query_operations(&ws, 1, 1)?
+            .operations
+            .remove(0)
+            .id
+    };
+
+    let (tx, rx) = channel::<SessionEvent>();
+    let (tx_load, rx_load) = channel::<Result<RepoConfig>>();
+    let (tx_view, rx_view) = channel::<Result<RepoConfig>>();
+    let (tx_mutate, rx_mutate) = channel::<MutationResult>();
+
+    tx.send(SessionEvent::OpenWorkspace {
+        tx: tx_load,
+        wd: Some(repo.path().to_owned()),
+    })?;
+    tx.send(SessionEvent::ViewOperation {
+        tx: tx_view,
+        op_id: Some(op_id),
+    })?;
+    tx.send(SessionEvent::ExecuteMutation {
+        tx: tx_mutate,
+        mutation: Box::new(AbandonRevisions {
+            ids: vec![revs::resolve_conflict().commit],
+        }),
+    })?;
+    tx.send(SessionEvent::EndSession)?;
+
+    WorkerSession::default().handle_events(&rx).await?;
+
+    _ = rx_load.recv()??;
+    let config = rx_view.recv()??;
+    assert!(matches!(
+        config,
+        RepoConfig::Workspace {
+            status: RepoStatus {
+                historical_operation: Some(_),
+                ..
+            },
+            ..
+        }
+    ));
+
+    let result = rx_mutate.recv()?;
+    assert!(matches!(result, MutationResult::PreconditionError { .. }));
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn config_read() -> Result<()> {
     let repo = mkrepo();
@@ -395,3 +459,214 @@ async fn config_write() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn reload_after_background_mutation() -> Result<()> {
+    let repo = mkrepo();
+
+    let mut session = WorkerSession::default();
+    let mut ws = session.load_directory(repo.path())?;
+
+    // a second session with its own repo handle, as used for git transfers
+    let (tx, rx) = channel::<SessionEvent>();
+    let (tx_load, rx_load) = channel::<Result<RepoConfig>>();
+    let (tx_mutate, rx_mutate) = channel::<MutationResult>();
+
+    tx.send(SessionEvent::OpenWorkspace {
+        tx: tx_load,
+        wd: Some(repo.path().to_owned()),
+    })?;
+    tx.send(SessionEvent::ExecuteMutation {
+        tx: tx_mutate,
+        mutation: Box::new(CreateRef {
+            id: revs::resolve_conflict(),
+            r#ref: StoreRef::LocalBookmark {
+                branch_name: "background".to_owned(),
+                has_conflict: false,
+                is_synced: true,
+                tracking_remotes: vec![],
+                available_remotes: 0,
+                potential_remotes: 0,
+            },
+        }),
+    })?;
+    tx.send(SessionEvent::EndSession)?;
+
+    WorkerSession::default().handle_events(&rx).await?;
+
+    _ = rx_load.recv()??;
+    let result = rx_mutate.recv()?;
+    assert!(matches!(result, MutationResult::Updated { .. }));
+
+    assert!(ws.load_at_head()?);
+    let rev = queries::query_revision(&ws, revs::resolve_conflict()).await?;
+    assert!(
+        matches!(rev, RevResult::Detail { header, .. } if header.refs.iter().any(|r#ref| matches!(
+            r#ref,
+            StoreRef::LocalBookmark { branch_name, .. } if branch_name == "background"
+        )))
+    );
+
+    Ok(())
+}
+
+/// a plain git repo with one commit on main
+fn mkgitrepo() -> Result<TempDir> {
+    let dir = tempdir()?;
+    let git = |args: &[&str]| -> Result<()> {
+        let status = Command::new("git")
+            .args(["-c", "user.name=gg", "-c", "user.email=gg@example.com"])
+            .args(args)
+            .current_dir(dir.path())
+            .status()?;
+        assert!(status.success(), "git {args:?}");
+        Ok(())
+    };
+    // not one of the names trunk() guesses, so clones have to ask for the default branch
+    git(&["init", "--initial-branch=develop"])?;
+    fs::write(dir.path().join("readme.txt"), "hello")?;
+    git(&["add", "readme.txt"])?;
+    git(&["commit", "-m", "initial"])?;
+    Ok(dir)
+}
+
+fn git_head(dir: &Path) -> Result<String> {
+    let output = Command::new("git")
+        .args(["rev-parse", "HEAD"])
+        .current_dir(dir)
+        .output()?;
+    Ok(String::from_utf8(output.stdout)?.trim().to_owned())
+}
+
+#[tokio::test]
+async fn create_workspace_init() -> Result<()> {
+    let dir = tempdir()?;
+    let wd = dir.path().join("new");
+
+    let (tx, rx) = channel::<SessionEvent>();
+    let (tx_create, rx_create) = channel::<Result<()>>();
+    let (tx_load, rx_load) = channel::<Result<RepoConfig>>();
+
+    tx.send(SessionEvent::CreateWorkspace {
+        tx: tx_create,
+        wd: wd.clone(),
+        source: WorkspaceSource::Init { colocate: false },
+    })?;
+    tx.send(SessionEvent::OpenWorkspace {
+        tx: tx_load,
+        wd: Some(wd),
+    })?;
+    tx.send(SessionEvent::EndSession)?;
+
+    WorkerSession::default().handle_events(&rx).await?;
+
+    rx_create.recv()??;
+    let config = rx_load.recv()??;
+    assert!(matches!(config, RepoConfig::Workspace { .. }));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn create_workspace_colocate() -> Result<()> {
+    let repo = mkgitrepo()?;
+    let head = git_head(repo.path())?;
+
+    let mut session = WorkerSession::default();
+    session.create_workspace(repo.path(), &WorkspaceSource::Colocate)?;
+
+    // the working copy starts on top of HEAD, leaving the files alone
+    let ws = session.load_directory(repo.path())?;
+    let wc = ws.get_commit(ws.wc_id())?;
+    assert_eq!(
+        vec![head],
+        wc.parent_ids()
+            .iter()
+            .map(|id| id.hex())
+            .collect::<Vec<_>>()
+    );
+    assert_eq!("hello", fs::read_to_string(repo.path().join("readme.txt"))?);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn create_workspace_clone() -> Result<()> {
+    let source = mkgitrepo()?;
+    let head = git_head(source.path())?;
+    let dir = tempdir()?;
+    let wd = dir.path().join("clone");
+
+    let mut session = WorkerSession::default();
+    session.create_workspace(
+        &wd,
+        &WorkspaceSource::Clone {
+            source: source.path().to_str().unwrap().to_owned(),
+            colocate: false,
+        },
+    )?;
+
+    // files are checked out on top of the remote's default branch, which is tracked and becomes trunk()
+    {
+        let ws = session.load_directory(&wd)?;
+        let wc = ws.get_commit(ws.wc_id())?;
+        assert_eq!(
+            vec![head.clone()],
+            wc.parent_ids()
+                .iter()
+                .map(|id| id.hex())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!("hello", fs::read_to_string(wd.join("readme.txt"))?);
+
+        assert!(
+            ws.view()
+                .get_local_bookmark(RefName::new("develop"))
+                .is_present()
+        );
+        let trunk = ws
+            .evaluate_revset_str("trunk()")?
+            .iter()
+            .next()
+            .transpose()?;
+        assert_eq!(Some(head), trunk.map(|id| id.hex()));
+    }
+
+    // cloning needs an empty directory
+    assert!(
+        session
+            .create_workspace(
+                &wd,
+                &WorkspaceSource::Clone {
+                    source: source.path().to_str().unwrap().to_owned(),
+                    colocate: false,
+                },
+            )
+            .is_err()
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn create_workspace_clone_failure() -> Result<()> {
+    let dir = tempdir()?;
+    let wd = dir.path().join("clone");
+
+    // a failed fetch leaves nothing behind, so the clone can be retried
+    let mut session = WorkerSession::default();
+    assert!(
+        session
+            .create_workspace(
+                &wd,
+                &WorkspaceSource::Clone {
+                    source: dir.path().join("missing").to_str().unwrap().to_owned(),
+                    colocate: true,
+                },
+            )
+            .is_err()
+    );
+    assert!(!wd.exists());
+
+    Ok(())
+}
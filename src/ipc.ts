@@ -108,32 +108,59 @@ export function trigger(command: string, request?: InvokeArgs) {
 }
 
 /**
- * call an IPC which, if successful, modifies the repo
+ * call an IPC which, if successful, modifies the repo. background mutations (git transfers) don't
+ * block the ui while they run, only reporting errors
  */
-export async function mutate<T>(command: string, mutation: T): Promise<boolean> {
+export async function mutate<T>(
+    command: string,
+    mutation: T,
+    options?: { background?: boolean }
+): Promise<boolean> {
     try {
         // set a wait state then the data state, unless the data comes in hella fast
         let fetch = invoke<MutationResult>(command, { mutation });
-        let result = await Promise.race([
-            fetch.then((r) => Promise.resolve<Query<MutationResult>>({ type: "data", value: r })),
-            delay<MutationResult>(),
-        ]);
-        currentMutation.set(result);
+        if (!options?.background) {
+            let result = await Promise.race([
+                fetch.then((r) =>
+                    Promise.resolve<Query<MutationResult>>({ type: "data", value: r })
+                ),
+                delay<MutationResult>(),
+            ]);
+            currentMutation.set(result);
+        }
         let value = await fetch;
 
-        // succeeded; dismiss modals
+        // succeeded, but some pushes were rejected or failed, there are absorb results or a
+        // reorder introduced conflicts; update and report them
         if (
+            value.type == "UpdatedWithErrors" ||
+            (value.type == "UpdatedPushes" &&
+                value.pushes.some((push) => push.outcome.type == "Rejected")) ||
+            value.type == "UpdatedAbsorb" ||
+            (value.type == "UpdatedReorder" &&
+                value.revisions.some((revision) => revision.conflicted_paths.length > 0))
+        ) {
+            repoStatusEvent.set(value.new_status);
+            currentMutation.set({ type: "data", value });
+
+            // succeeded; dismiss modals
+        } else if (
             value.type == "Updated" ||
             value.type == "UpdatedSelection" ||
-            value.type == "Unchanged"
+            value.type == "UpdatedPushes" ||
+            value.type == "UpdatedReorder" ||
+            value.type == "Unchanged" ||
+            value.type == "Cancelled"
         ) {
-            if (value.type != "Unchanged") {
+            if (value.type != "Unchanged" && value.type != "Cancelled") {
                 repoStatusEvent.set(value.new_status);
                 if (value.type == "UpdatedSelection") {
                     revisionSelectEvent.set(value.new_selection);
                 }
             }
-            currentMutation.set(null);
+            if (!options?.background) {
+                currentMutation.set(null);
+            }
 
             // failed; transition from overlay or delay to error
         } else {
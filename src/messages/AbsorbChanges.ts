@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { RevId } from "./RevId";
+import type { TreePath } from "./TreePath";
+
+/**
+ * Moves each hunk of a revision into the closest mutable ancestor which last changed those lines,
+ * like `jj absorb`. An empty list of paths absorbs every file.
+ */
+export type AbsorbChanges = { from_id: RevId; paths: Array<TreePath> };
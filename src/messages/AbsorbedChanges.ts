@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { RevChange } from "./RevChange";
+import type { RevHeader } from "./RevHeader";
+
+/**
+ * The hunks one revision received from an absorb
+ */
+export type AbsorbedChanges = { revision: RevHeader; changes: Array<RevChange> };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Adds a git remote, optionally with a separate URL to push to
+ */
+export type AddRemote = { remote_name: string; url: string; push_url: string | null };
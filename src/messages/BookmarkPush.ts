@@ -0,0 +1,13 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { PushAction } from "./PushAction";
+import type { RevHeader } from "./RevHeader";
+
+/**
+ * An update to one bookmark on a remote
+ */
+export type BookmarkPush = {
+    branch_name: string;
+    action: PushAction;
+    old_target: RevHeader | null;
+    new_target: RevHeader | null;
+};
@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CommitId } from "./CommitId";
+import type { OperationHeader } from "./OperationHeader";
+import type { RevChange } from "./RevChange";
+import type { RevHeader } from "./RevHeader";
+
+/**
+ * A commit which a change has pointed to, with the operation that created it
+ */
+export type EvolutionEntry = {
+    header: RevHeader;
+    operation: OperationHeader | null;
+    predecessor_ids: Array<CommitId>;
+    /**
+     * Relative to the predecessors, rebased onto this commit's parents
+     */
+    changes: Array<RevChange>;
+};
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { EvolutionEntry } from "./EvolutionEntry";
+import type { RevId } from "./RevId";
+
+export type EvolutionResult =
+    | { type: "NotFound"; id: RevId }
+    | { type: "Detail"; entries: Array<EvolutionEntry> };
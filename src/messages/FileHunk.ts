@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ChangeHunk } from "./ChangeHunk";
+import type { TreePath } from "./TreePath";
+
+/**
+ * A hunk in one file
+ */
+export type FileHunk = { path: TreePath; hunk: ChangeHunk };
@@ -1,7 +1,12 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { RevId } from "./RevId";
 import type { StoreRef } from "./StoreRef";
 
 export type GitPush =
     | { type: "AllBookmarks"; remote_name: string }
     | { type: "AllRemotes"; branch_ref: StoreRef }
-    | { type: "RemoteBookmark"; remote_name: string; branch_ref: StoreRef };
+    | { type: "RemoteBookmark"; remote_name: string; branch_ref: StoreRef }
+    /**
+     * Creates or moves a bookmark named after the revision's change, then pushes it
+     */
+    | { type: "Revision"; id: RevId; remote_name: string };
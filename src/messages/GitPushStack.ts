@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { RevId } from "./RevId";
+
+/**
+ * Pushes every mutable revision between trunk() and a head, each under a bookmark named after
+ * its change
+ */
+export type GitPushStack = { head_id: RevId; remote_name: string };
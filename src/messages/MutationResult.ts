@@ -1,7 +1,11 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AbsorbedChanges } from "./AbsorbedChanges";
 import type { MultilineString } from "./MultilineString";
+import type { ReorderedRevision } from "./ReorderedRevision";
 import type { RepoStatus } from "./RepoStatus";
 import type { RevHeader } from "./RevHeader";
+import type { RevisionPush } from "./RevisionPush";
+import type { SkippedPath } from "./SkippedPath";
 
 /**
  * Common result type for mutating commands
@@ -11,4 +15,29 @@ export type MutationResult =
     | { type: "Updated"; new_status: RepoStatus }
     | { type: "UpdatedSelection"; new_status: RepoStatus; new_selection: RevHeader }
     | { type: "PreconditionError"; message: string }
-    | { type: "InternalError"; message: MultilineString };
+    | { type: "InternalError"; message: MultilineString }
+    /**
+     * Stopped by a cancel_operation request; nothing was changed
+     */
+    | { type: "Cancelled" }
+    /**
+     * A stack push ran, though the remote may have rejected some of its bookmarks
+     */
+    | { type: "UpdatedPushes"; new_status: RepoStatus; pushes: Array<RevisionPush> }
+    /**
+     * Hunks were absorbed into the listed revisions; any skipped paths stay in the source
+     */
+    | {
+          type: "UpdatedAbsorb";
+          new_status: RepoStatus;
+          absorbed: Array<AbsorbedChanges>;
+          skipped_paths: Array<SkippedPath>;
+      }
+    /**
+     * Revisions were rewritten in the listed order, each with any paths which now conflict
+     */
+    | { type: "UpdatedReorder"; new_status: RepoStatus; revisions: Array<ReorderedRevision> }
+    /**
+     * Part of the work was committed, but some steps failed; the message lists them
+     */
+    | { type: "UpdatedWithErrors"; new_status: RepoStatus; message: string };
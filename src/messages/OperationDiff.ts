@@ -0,0 +1,17 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { OperationId } from "./OperationId";
+import type { RefDiff } from "./RefDiff";
+import type { RevHeader } from "./RevHeader";
+import type { RewrittenRevision } from "./RewrittenRevision";
+
+/**
+ * Changes to the visible revisions and refs between two operations
+ */
+export type OperationDiff = {
+    from_op: OperationId;
+    to_op: OperationId;
+    added: Array<RevHeader>;
+    rewritten: Array<RewrittenRevision>;
+    abandoned: Array<RevHeader>;
+    refs: Array<RefDiff>;
+};
@@ -0,0 +1,13 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { OperationId } from "./OperationId";
+
+export type OperationHeader = {
+    id: OperationId;
+    description: string;
+    username: string;
+    hostname: string;
+    time: string;
+    tags: { [key in string]?: string };
+    is_snapshot: boolean;
+    is_current: boolean;
+};
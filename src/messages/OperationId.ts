@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * An operation id with a short prefix for display
+ */
+export type OperationId = { hex: string; prefix: string };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { TreePath } from "./TreePath";
+
+/**
+ * A new file too large to snapshot, according to snapshot.max-new-file-size
+ */
+export type OversizedFile = { path: TreePath; size: number; max_size: number };
@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Status of a long-running worker operation, such as a git transfer or snapshot
+ */
+export type ProgressEvent = {
+    operation: string;
+    /**
+     * Between 0 and 1, or absent if the total amount of work is unknown
+     */
+    fraction: number | null;
+    detail: string | null;
+    /**
+     * Passed to cancel_operation to skip any remaining steps; absent if there are none, since a
+     * transfer in flight always completes
+     */
+    cancel_id: number | null;
+    finished: boolean;
+};
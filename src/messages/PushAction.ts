@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type PushAction =
+    | "Create"
+    | "Move"
+    /**
+     * The remote bookmark's commit is not an ancestor of the new target
+     */
+    | "ForceMove"
+    | "Delete";
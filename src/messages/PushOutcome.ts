@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { PushAction } from "./PushAction";
+
+export type PushOutcome =
+    | { type: "Pushed"; action: PushAction }
+    | { type: "UpToDate" }
+    | { type: "Rejected"; reason: string };
@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CommitId } from "./CommitId";
+import type { StoreRef } from "./StoreRef";
+
+/**
+ * A ref whose targets differ between two operations
+ */
+export type RefDiff = {
+    ref: StoreRef;
+    old_targets: Array<CommitId>;
+    new_targets: Array<CommitId>;
+};
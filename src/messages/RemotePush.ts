@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { BookmarkPush } from "./BookmarkPush";
+import type { RevHeader } from "./RevHeader";
+
+/**
+ * Everything a push would send to one remote
+ */
+export type RemotePush = {
+    remote_name: string;
+    bookmarks: Array<BookmarkPush>;
+    /**
+     * Commits not yet reachable from any of the remote's bookmarks
+     */
+    new_commits: Array<RevHeader>;
+    has_more_commits: boolean;
+};
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Removes a git remote along with its remote bookmarks
+ */
+export type RemoveRemote = { remote_name: string };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Renames a git remote, moving its remote bookmarks to the new name
+ */
+export type RenameRemote = { old_name: string; new_name: string };
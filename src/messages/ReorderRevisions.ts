@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { RevId } from "./RevId";
+
+/**
+ * Stacks revisions onto a base in the given order, bottom first, as a single operation.
+ * Descendants which aren't listed stay on top of the revision they descended from.
+ */
+export type ReorderRevisions = { base: RevId; order: Array<RevId> };
@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { RevHeader } from "./RevHeader";
+import type { TreePath } from "./TreePath";
+
+/**
+ * One revision of a reordered stack and the paths left conflicted in it
+ */
+export type ReorderedRevision = { revision: RevHeader; conflicted_paths: Array<TreePath> };
@@ -1,4 +1,25 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 import type { CommitId } from "./CommitId";
+import type { OperationId } from "./OperationId";
+import type { OversizedFile } from "./OversizedFile";
 
-export type RepoStatus = { operation_description: string; working_copy: CommitId };
+export type RepoStatus = {
+    operation_description: string;
+    working_copy: CommitId;
+    /**
+     * Set when viewing the repo at a past operation, in which case it can't be modified
+     */
+    historical_operation: OperationId | null;
+    /**
+     * Includes gg.mutation, gg.changes and gg.version for operations created by gg
+     */
+    operation_tags: { [key in string]?: string };
+    /**
+     * New files which the latest snapshot left untracked because they were too large
+     */
+    oversized_files: Array<OversizedFile>;
+    /**
+     * Set when the latest snapshot failed, e.g. because the working copy is stale
+     */
+    snapshot_error: string | null;
+};
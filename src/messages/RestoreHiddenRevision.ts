@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { RevId } from "./RevId";
+
+/**
+ * Makes a hidden commit visible again, either as a new change or in place of its change's current commit
+ */
+export type RestoreHiddenRevision = { id: RevId; as_new_change: boolean };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { OperationId } from "./OperationId";
+
+/**
+ * Resets the repo to the state recorded by an earlier operation
+ */
+export type RestoreOperation = { op_id: OperationId };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { OperationId } from "./OperationId";
+
+/**
+ * Applies the inverse of an earlier operation, keeping any operations which followed it
+ */
+export type RevertOperation = { op_id: OperationId };
@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { PushOutcome } from "./PushOutcome";
+import type { RevHeader } from "./RevHeader";
+
+/**
+ * What happened to one revision's bookmark in a stack push
+ */
+export type RevisionPush = { revision: RevHeader; branch_name: string; outcome: PushOutcome };
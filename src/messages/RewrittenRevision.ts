@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CommitId } from "./CommitId";
+import type { RevHeader } from "./RevHeader";
+
+/**
+ * A revision which replaced one or more commits visible before the operation
+ */
+export type RewrittenRevision = { header: RevHeader; old_ids: Array<CommitId> };
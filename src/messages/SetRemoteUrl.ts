@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Changes the URL a git remote fetches from, and the one it pushes to if given
+ */
+export type SetRemoteUrl = { remote_name: string; url: string; push_url: string | null };
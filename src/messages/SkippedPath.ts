@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { TreePath } from "./TreePath";
+
+/**
+ * A path whose changes couldn't be absorbed, such as a conflicted or binary file
+ */
+export type SkippedPath = { path: TreePath; reason: string };
@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Where a split's new revision goes, relative to the revision it was split from
+ */
+export type SplitPlacement =
+    /**
+     * The new revision becomes the original's parent
+     */
+    | "Before"
+    /**
+     * The new revision becomes the original's child, taking over its descendants
+     */
+    | "After"
+    /**
+     * The new revision shares the original's parents, and the original's children merge both
+     */
+    | "Sibling";
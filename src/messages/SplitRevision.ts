@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { FileHunk } from "./FileHunk";
+import type { RevId } from "./RevId";
+import type { SplitPlacement } from "./SplitPlacement";
+import type { TreePath } from "./TreePath";
+
+/**
+ * Moves whole files and individual hunks out of a revision into a new revision
+ */
+export type SplitRevision = {
+    id: RevId;
+    paths: Array<TreePath>;
+    hunks: Array<FileHunk>;
+    placement: SplitPlacement;
+};
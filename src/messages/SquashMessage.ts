@@ -0,0 +1,13 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Which description a squash leaves on its destination
+ */
+export type SquashMessage =
+    | { type: "Source" }
+    | { type: "Destination" }
+    /**
+     * Both descriptions, destination first; empty ones are skipped
+     */
+    | { type: "Combined" }
+    | { type: "Custom"; description: string };
@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CommitId } from "./CommitId";
+import type { RevId } from "./RevId";
+import type { SquashMessage } from "./SquashMessage";
+
+/**
+ * Moves all of a revision's changes into another revision and abandons the emptied source
+ */
+export type SquashRevision = { from_id: RevId; to_id: CommitId; message: SquashMessage };
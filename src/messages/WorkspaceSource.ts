@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * How to create a workspace in a directory which doesn't have one
+ */
+export type WorkspaceSource =
+    /**
+     * A new, empty repo
+     */
+    | { type: "Init"; colocate: boolean }
+    /**
+     * Shares the git repo already in the directory
+     */
+    | { type: "Colocate" }
+    /**
+     * Copies a repo from a URL or local path, which becomes the origin remote
+     */
+    | { type: "Clone"; source: string; colocate: boolean };
@@ -1,6 +1,8 @@
 import type { RevHeader } from "../messages/RevHeader";
+import type { AbsorbChanges } from "../messages/AbsorbChanges";
 import type { CopyChanges } from "../messages/CopyChanges";
 import type { MoveChanges } from "../messages/MoveChanges";
+import type { SplitRevision } from "../messages/SplitRevision";
 import type { TreePath } from "../messages/TreePath";
 import { mutate } from "../ipc";
 
@@ -25,6 +27,12 @@ export default class ChangeMutator {
             case "restore":
                 this.onRestore();
                 break;
+            case "split":
+                this.onSplit();
+                break;
+            case "absorb":
+                this.onAbsorb();
+                break;
             default:
                 console.log(`unimplemented mutation '${event}'`, this);
         }
@@ -45,4 +53,21 @@ export default class ChangeMutator {
             paths: [this.#path],
         });
     };
+
+    // like jj split, the selected file goes into a new revision before the rest of the changes
+    onSplit = () => {
+        mutate<SplitRevision>("split_revision", {
+            id: this.#revision.id,
+            paths: [this.#path],
+            hunks: [],
+            placement: "Before",
+        });
+    };
+
+    onAbsorb = () => {
+        mutate<AbsorbChanges>("absorb_changes", {
+            from_id: this.#revision.id,
+            paths: [this.#path],
+        });
+    };
 }
@@ -5,8 +5,36 @@ import type { RenameBranch } from "../messages/RenameBranch";
 import type { GitPush } from "../messages/GitPush";
 import type { GitFetch } from "../messages/GitFetch";
 import type { DeleteRef } from "../messages/DeleteRef";
+import type { PushPlan } from "../messages/PushPlan";
 import { getInput, mutate, query } from "../ipc";
 
+/**
+ * previews a push, asking for confirmation if it would rewrite or delete remote bookmarks
+ */
+export async function pushWithConfirmation(push: GitPush) {
+    let plan = await query<PushPlan>("query_push_plan", { push });
+    if (plan.type == "data" && plan.value.type == "Ready") {
+        let risky = plan.value.remotes.flatMap((remote) =>
+            remote.bookmarks
+                .filter((bookmark) => bookmark.action == "ForceMove" || bookmark.action == "Delete")
+                .map((bookmark) => `${bookmark.branch_name}@${remote.remote_name}`)
+        );
+        if (risky.length > 0) {
+            let response = await getInput(
+                "Confirm Push",
+                `This push will force-move or delete ${risky.join(", ")}.`,
+                []
+            );
+            if (!response) {
+                return;
+            }
+        }
+    }
+
+    // if the plan failed or was blocked, the push will report the reason
+    mutate<GitPush>("git_push", push, { background: true });
+}
+
 export default class RefMutator {
     #ref: StoreRef;
 
@@ -93,7 +121,7 @@ export default class RefMutator {
                 break;
 
             case "RemoteBookmark":
-                mutate<GitPush>("git_push", {
+                pushWithConfirmation({
                     type: "RemoteBookmark",
                     remote_name: this.#ref.remote_name,
                     branch_ref: this.#ref,
@@ -101,7 +129,7 @@ export default class RefMutator {
                 break;
 
             case "LocalBookmark":
-                mutate<GitPush>("git_push", {
+                pushWithConfirmation({
                     type: "AllRemotes",
                     branch_ref: this.#ref,
                 });
@@ -131,7 +159,7 @@ export default class RefMutator {
                 ]);
                 if (response) {
                     let remote_name = response["Remote Name"];
-                    mutate<GitPush>("git_push", {
+                    pushWithConfirmation({
                         type: "RemoteBookmark",
                         remote_name,
                         branch_ref: this.#ref,
@@ -148,17 +176,25 @@ export default class RefMutator {
                 break;
 
             case "RemoteBookmark":
-                mutate<GitFetch>("git_fetch", {
-                    type: "AllRemotes",
-                    branch_ref: this.#ref,
-                });
+                mutate<GitFetch>(
+                    "git_fetch",
+                    {
+                        type: "AllRemotes",
+                        branch_ref: this.#ref,
+                    },
+                    { background: true }
+                );
                 break;
 
             case "LocalBookmark":
-                mutate<GitFetch>("git_fetch", {
-                    type: "AllRemotes",
-                    branch_ref: this.#ref,
-                });
+                mutate<GitFetch>(
+                    "git_fetch",
+                    {
+                        type: "AllRemotes",
+                        branch_ref: this.#ref,
+                    },
+                    { background: true }
+                );
                 break;
         }
     };
@@ -187,11 +223,15 @@ export default class RefMutator {
                 ]);
                 if (response) {
                     let remote_name = response["Remote Name"];
-                    mutate<GitFetch>("git_fetch", {
-                        type: "RemoteBookmark",
-                        remote_name,
-                        branch_ref: this.#ref,
-                    });
+                    mutate<GitFetch>(
+                        "git_fetch",
+                        {
+                            type: "RemoteBookmark",
+                            remote_name,
+                            branch_ref: this.#ref,
+                        },
+                        { background: true }
+                    );
                 }
                 break;
         }
@@ -1,6 +1,7 @@
 import type { RevHeader } from "../messages/RevHeader";
 import type { RevId } from "../messages/RevId";
 import type { AbandonRevisions } from "../messages/AbandonRevisions";
+import type { AbsorbChanges } from "../messages/AbsorbChanges";
 import type { BackoutRevisions } from "../messages/BackoutRevisions";
 import type { CheckoutRevision } from "../messages/CheckoutRevision";
 import type { CopyChanges } from "../messages/CopyChanges";
@@ -8,9 +9,12 @@ import type { CreateRevision } from "../messages/CreateRevision";
 import type { CreateRevisionBetween } from "../messages/CreateRevisionBetween";
 import type { DescribeRevision } from "../messages/DescribeRevision";
 import type { DuplicateRevisions } from "../messages/DuplicateRevisions";
-import type { MoveChanges } from "../messages/MoveChanges";
+import type { SquashMessage } from "../messages/SquashMessage";
+import type { SquashRevision } from "../messages/SquashRevision";
 import type { CreateRef } from "../messages/CreateRef";
-import { getInput, mutate } from "../ipc";
+import type { GitPushStack } from "../messages/GitPushStack";
+import { getInput, mutate, query } from "../ipc";
+import { pushWithConfirmation } from "./RefMutator";
 import type { StoreRef } from "../messages/StoreRef";
 
 export default class RevisionMutator {
@@ -54,6 +58,16 @@ export default class RevisionMutator {
                     this.onSquash();
                 }
                 break;
+            case "squash_with_message":
+                if (!this.#revision.is_immutable && this.#revision.parent_ids.length == 1) {
+                    this.onSquashWithMessage();
+                }
+                break;
+            case "absorb":
+                if (!this.#revision.is_immutable) {
+                    this.onAbsorb();
+                }
+                break;
             case "restore":
                 if (!this.#revision.is_immutable && this.#revision.parent_ids.length == 1) {
                     this.onRestore();
@@ -62,6 +76,14 @@ export default class RevisionMutator {
             case "branch":
                 this.onBranch();
                 break;
+            case "push":
+                this.onPush();
+                break;
+            case "push_stack":
+                if (!this.#revision.is_immutable) {
+                    this.onPushStack();
+                }
+                break;
             default:
                 console.log(`unimplemented mutation '${event}'`, this);
         }
@@ -122,10 +144,42 @@ export default class RevisionMutator {
         });
     };
 
-    onSquash = () => {
-        mutate<MoveChanges>("move_changes", {
+    onSquash = (message: SquashMessage = { type: "Combined" }) => {
+        mutate<SquashRevision>("squash_revision", {
             from_id: this.#revision.id,
             to_id: this.#revision.parent_ids[0],
+            message,
+        });
+    };
+
+    onSquashWithMessage = async () => {
+        let response = await getInput("Squash into Parent", "", [
+            { label: "Description", choices: ["Combine both", "Keep this", "Keep parent's", "New"] },
+            { label: "New description", choices: [] },
+        ]);
+        if (!response) {
+            return;
+        }
+
+        switch (response["Description"]) {
+            case "Combine both":
+                this.onSquash({ type: "Combined" });
+                break;
+            case "Keep this":
+                this.onSquash({ type: "Source" });
+                break;
+            case "Keep parent's":
+                this.onSquash({ type: "Destination" });
+                break;
+            case "New":
+                this.onSquash({ type: "Custom", description: response["New description"] });
+                break;
+        }
+    };
+
+    onAbsorb = () => {
+        mutate<AbsorbChanges>("absorb_changes", {
+            from_id: this.#revision.id,
             paths: [],
         });
     };
@@ -153,4 +207,39 @@ export default class RevisionMutator {
             mutate<CreateRef>("create_ref", { ref, id: this.#revision.id });
         }
     };
+
+    onPush = async () => {
+        let remote_name = await this.#selectRemote();
+        if (remote_name) {
+            pushWithConfirmation({ type: "Revision", id: this.#revision.id, remote_name });
+        }
+    };
+
+    onPushStack = async () => {
+        let remote_name = await this.#selectRemote();
+        if (remote_name) {
+            mutate<GitPushStack>(
+                "git_push_stack",
+                { head_id: this.#revision.id, remote_name },
+                { background: true }
+            );
+        }
+    };
+
+    // only asks which remote if there's a choice
+    async #selectRemote(): Promise<string | null> {
+        let allRemotes = await query<string[]>("query_remotes", { tracking_branch: null });
+        if (allRemotes.type == "error") {
+            console.log("error loading remotes: " + allRemotes.message);
+            return null;
+        }
+
+        if (allRemotes.value.length > 1) {
+            let response = await getInput("Select Remote", "", [
+                { label: "Remote Name", choices: allRemotes.value },
+            ]);
+            return response ? response["Remote Name"] : null;
+        }
+        return allRemotes.value[0] ?? null;
+    }
 }
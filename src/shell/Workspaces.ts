@@ -0,0 +1,16 @@
+import { getInput, trigger } from "../ipc";
+import type { WorkspaceSource } from "../messages/WorkspaceSource";
+
+/**
+ * sets up a repo and opens it. without a path, the backend asks where to put it
+ */
+export function createWorkspace(source: WorkspaceSource, path: string | null = null) {
+    trigger("create_workspace", { path, source });
+}
+
+export async function cloneWorkspace(path: string | null = null) {
+    let response = await getInput("Clone Repository", "", ["URL or path"]);
+    if (response) {
+        createWorkspace({ type: "Clone", source: response["URL or path"], colocate: true }, path);
+    }
+}
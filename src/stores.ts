@@ -8,6 +8,7 @@ import { event, type Query } from "./ipc";
 import type { InputRequest } from "./messages/InputRequest";
 import type { InputResponse } from "./messages/InputResponse";
 import type { RevChange } from "./messages/RevChange";
+import type { ProgressEvent } from "./messages/ProgressEvent";
 
 export const repoConfigEvent = await event<RepoConfig>("gg://repo/config", { type: "Initial" });
 export const repoStatusEvent = await event<RepoStatus | undefined>("gg://repo/status", undefined);
@@ -20,6 +21,8 @@ export const changeSelectEvent = await event<RevChange | undefined>(
     undefined
 );
 
+export const progressEvent = await event<ProgressEvent | undefined>("gg://progress", undefined);
+
 export const currentMutation = writable<Query<MutationResult> | null>(null);
 export const currentContext = writable<Operand | null>();
 export const currentSource = writable<Operand | null>();